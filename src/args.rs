@@ -1,49 +1,175 @@
-use std::{env, str::FromStr};
+use std::{env, path::PathBuf, str::FromStr};
 
-use crate::poker::{
-    action::Action,
-    betsize::Betsize,
-    board::{connection::Connection, height::BoardHeight, pair::BoardPair, suit::BoardSuit},
-    position::{Position, Positions},
+use crate::{
+    cache::{self, CacheAction},
+    calculation::{
+        boardset::BoardSetPolicy, expression::Expression, ingestion::Ingestion, weight::Weighting,
+    },
+    config,
+    error::Error,
+    poker::{
+        action::Action,
+        betsize::Betsize,
+        board::{
+            connection::Connection,
+            connectivity::ConnectivityRange,
+            flush::FlushDraw,
+            height::BoardHeight,
+            pair::{BoardPair, PairPosition},
+            pattern::BoardPattern,
+            suit::BoardSuit,
+            turn::TurnCard,
+        },
+        position::{Position, Positions},
+        rank::{Rank, RankRange},
+        ParseError,
+    },
 };
 
+pub enum Command {
+    Aggregate(Args),
+    List(PathBuf),
+    Coverage(Args),
+    Cache(CacheAction, PathBuf),
+}
+
 pub struct Args {
+    pub data_dir: PathBuf,
     pub positions: Positions,
     pub betsizes: Vec<Betsize>,
     pub heights: Vec<BoardHeight>,
     pub suits: Vec<BoardSuit>,
+    pub flush_draws: Vec<FlushDraw>,
     pub connections: Vec<Connection>,
+    pub connectivity: Vec<ConnectivityRange>,
     pub pair: Vec<BoardPair>,
+    pub turn_cards: Vec<TurnCard>,
+    pub high_cards: Vec<RankRange>,
+    pub second_cards: Vec<RankRange>,
+    pub ranks: Vec<Rank>,
+    pub pair_positions: Vec<PairPosition>,
+    pub patterns: Vec<BoardPattern>,
+    // Applies on top of the texture options above
+    pub filter: Option<Expression>,
     pub actions: Vec<Action>,
+    // Derived from the bets of the spot's size files when not given
+    pub pot: Option<f32>,
+    pub weighting: Weighting,
+    pub board_set_policy: BoardSetPolicy,
+    pub ingestion: Ingestion,
+    // None when parsed reports are not cached
+    pub cache_dir: Option<PathBuf>,
+}
+
+pub fn read_cmdline_args() -> Result<Command, Error> {
+    parse_command(env::args().skip(1))
+}
+
+fn parse_command(args: impl Iterator<Item = String>) -> Result<Command, Error> {
+    let mut args = args.peekable();
+
+    if args
+        .next_if(|arg| arg.eq_ignore_ascii_case("list"))
+        .is_some()
+    {
+        Ok(Command::List(parse_list_args(args)?))
+    } else if args
+        .next_if(|arg| arg.eq_ignore_ascii_case("coverage"))
+        .is_some()
+    {
+        Ok(Command::Coverage(parse_args(args)?))
+    } else if args
+        .next_if(|arg| arg.eq_ignore_ascii_case("cache"))
+        .is_some()
+    {
+        let action = args
+            .next()
+            .ok_or_else(|| Error::Args(String::from("expected CLEAR or REBUILD after cache")))?;
+        let action = CacheAction::from_str(&action.to_uppercase())?;
+
+        Ok(Command::Cache(action, parse_list_args(args)?))
+    } else {
+        Ok(Command::Aggregate(parse_args(args)?))
+    }
 }
 
-pub fn read_cmdline_args() -> Args {
-    parse_args(env::args().skip(1))
+fn parse_list_args(mut args: impl Iterator<Item = String>) -> Result<PathBuf, Error> {
+    let mut data_dir: Option<PathBuf> = None;
+
+    while let Some(arg) = args.next() {
+        match &arg.to_uppercase()[..] {
+            "-D" => {
+                let dir = args
+                    .next()
+                    .ok_or_else(|| Error::Args(String::from("expected a directory after -d")))?;
+                data_dir = Some(PathBuf::from(dir));
+            }
+            _ => return Err(Error::Args(format!("unexpected argument: {arg}"))),
+        }
+    }
+
+    Ok(config::resolve_data_dir(data_dir))
 }
 
-fn parse_args(args: impl Iterator<Item = String>) -> Args {
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Args, Error> {
     enum ParseMode {
         None,
+        DataDir,
         Positions,
         Betsizes,
         Heights,
         Suits,
+        FlushDraws,
         Connectednesses,
+        Connectivity,
         Pair,
+        TurnCards,
+        HighCards,
+        SecondCards,
+        Ranks,
+        PairPositions,
+        Patterns,
+        Filter,
+        Actions,
+        Pot,
+        Weighting,
+        BoardSetPolicy,
+        Ingestion,
     }
 
     let mut positions: Vec<Position> = Vec::new();
+    // Left empty to use every size present for the spot
     let mut betsizes: Vec<Betsize> = Vec::new();
     let mut heights: Vec<BoardHeight> = Vec::new();
     let mut suits: Vec<BoardSuit> = Vec::new();
+    let mut flush_draws: Vec<FlushDraw> = Vec::new();
     let mut connections: Vec<Connection> = Vec::new();
+    let mut connectivity: Vec<ConnectivityRange> = Vec::new();
     let mut pair: Vec<BoardPair> = Vec::new();
+    let mut turn_cards: Vec<TurnCard> = Vec::new();
+    let mut high_cards: Vec<RankRange> = Vec::new();
+    let mut second_cards: Vec<RankRange> = Vec::new();
+    let mut ranks: Vec<Rank> = Vec::new();
+    let mut pair_positions: Vec<PairPosition> = Vec::new();
+    let mut patterns: Vec<BoardPattern> = Vec::new();
+    let mut filter: Option<Expression> = None;
+    // IP after OOP checked, unless another node is given
+    let mut actions: Vec<Action> = vec![Action::Check];
+    let mut pot: Option<f32> = None;
+    let mut weighting = Weighting::Combinatorial;
+    let mut board_set_policy = BoardSetPolicy::Abort;
+    let mut ingestion = Ingestion::Strict;
+    let mut use_cache = true;
+    let mut data_dir: Option<PathBuf> = None;
 
     let mut curr_parse_mode: ParseMode = ParseMode::None;
 
-    let args_uppercase = args.map(|arg| arg.to_uppercase());
-    for arg in args_uppercase {
-        match &arg[..] {
+    for arg in args {
+        match &arg.to_uppercase()[..] {
+            "-D" => {
+                curr_parse_mode = ParseMode::DataDir;
+                continue;
+            }
             "-PO" => {
                 curr_parse_mode = ParseMode::Positions;
                 continue;
@@ -60,55 +186,179 @@ fn parse_args(args: impl Iterator<Item = String>) -> Args {
                 curr_parse_mode = ParseMode::Suits;
                 continue;
             }
+            "-FD" => {
+                curr_parse_mode = ParseMode::FlushDraws;
+                continue;
+            }
             "-C" => {
                 curr_parse_mode = ParseMode::Connectednesses;
                 continue;
             }
+            "-CM" => {
+                curr_parse_mode = ParseMode::Connectivity;
+                continue;
+            }
             "-PA" => {
                 curr_parse_mode = ParseMode::Pair;
                 continue;
             }
+            "-T" => {
+                curr_parse_mode = ParseMode::TurnCards;
+                continue;
+            }
+            "-HC" => {
+                curr_parse_mode = ParseMode::HighCards;
+                continue;
+            }
+            "-SC" => {
+                curr_parse_mode = ParseMode::SecondCards;
+                continue;
+            }
+            "-R" => {
+                curr_parse_mode = ParseMode::Ranks;
+                continue;
+            }
+            "-PP" => {
+                curr_parse_mode = ParseMode::PairPositions;
+                continue;
+            }
+            "-BP" => {
+                curr_parse_mode = ParseMode::Patterns;
+                continue;
+            }
+            "-F" => {
+                curr_parse_mode = ParseMode::Filter;
+                continue;
+            }
+            "-A" => {
+                curr_parse_mode = ParseMode::Actions;
+                continue;
+            }
+            "-POT" => {
+                curr_parse_mode = ParseMode::Pot;
+                continue;
+            }
+            "-W" => {
+                curr_parse_mode = ParseMode::Weighting;
+                continue;
+            }
+            "-M" => {
+                curr_parse_mode = ParseMode::BoardSetPolicy;
+                continue;
+            }
+            "-I" => {
+                curr_parse_mode = ParseMode::Ingestion;
+                continue;
+            }
+            "-NC" => {
+                use_cache = false;
+                curr_parse_mode = ParseMode::None;
+                continue;
+            }
 
             token => match curr_parse_mode {
-                ParseMode::Positions => positions.push(Position::from_str(token).unwrap()),
-                ParseMode::Betsizes => betsizes.push(Betsize::from_str(token).unwrap()),
-                ParseMode::Heights => heights.push(BoardHeight::try_from(token).unwrap()),
-                ParseMode::Suits => suits.push(BoardSuit::try_from(token).unwrap()),
-                ParseMode::Connectednesses => {
-                    connections.push(Connection::try_from(token).unwrap())
+                ParseMode::DataDir => data_dir = Some(PathBuf::from(&arg)),
+                ParseMode::Positions => positions.push(Position::from_str(token)?),
+                ParseMode::Betsizes => betsizes.push(Betsize::from_str(token)?),
+                ParseMode::Heights => heights.push(BoardHeight::try_from(token)?),
+                ParseMode::Suits => suits.push(BoardSuit::try_from(token)?),
+                ParseMode::FlushDraws => flush_draws.push(FlushDraw::try_from(token)?),
+                ParseMode::Connectednesses => connections.push(Connection::try_from(token)?),
+                ParseMode::Connectivity => connectivity.push(ConnectivityRange::from_str(token)?),
+                ParseMode::Pair => pair.push(BoardPair::from_str(token)?),
+                ParseMode::TurnCards => turn_cards.push(TurnCard::try_from(token)?),
+                ParseMode::HighCards => high_cards.push(RankRange::from_str(token)?),
+                ParseMode::SecondCards => second_cards.push(RankRange::from_str(token)?),
+                ParseMode::Ranks => ranks.push(Rank::from_str(token)?),
+                ParseMode::PairPositions => pair_positions.push(PairPosition::from_str(token)?),
+                ParseMode::Patterns => patterns.push(BoardPattern::from_str(token)?),
+                ParseMode::Filter => {
+                    filter = Some(Expression::from_str(&arg)?);
+                    // The expression is a single argument, an unquoted one fails on its rest
+                    curr_parse_mode = ParseMode::None;
+                }
+                ParseMode::Actions => actions = Action::parse_path(token)?,
+                ParseMode::Pot => {
+                    pot = Some(
+                        f32::from_str(token)
+                            .ok()
+                            .filter(|pot| *pot > 0.0)
+                            .ok_or_else(|| ParseError::str("pot", token))?,
+                    )
                 }
-                ParseMode::Pair => pair.push(BoardPair::from_str(token).unwrap()),
-                _ => panic!(),
+                ParseMode::Weighting => weighting = Weighting::from_str(token)?,
+                ParseMode::BoardSetPolicy => board_set_policy = BoardSetPolicy::from_str(token)?,
+                ParseMode::Ingestion => ingestion = Ingestion::from_str(token)?,
+                ParseMode::None => return Err(Error::Args(format!("unexpected argument: {arg}"))),
             },
         }
     }
 
-    assert!(positions.len() == 2);
-    let pos1 = positions.first().unwrap().to_owned();
-    let pos2 = positions.get(1).unwrap().to_owned();
-    let positions = Positions {
-        ip: pos1,
-        oop: pos2,
-    };
+    let [pos1, pos2] = <[Position; 2]>::try_from(positions).map_err(|positions| {
+        Error::Args(format!(
+            "expected two positions after -po, got {}",
+            positions.len()
+        ))
+    })?;
+    let positions = Positions::new(pos1, pos2)?;
 
-    if betsizes.is_empty() {
-        betsizes = vec![
-            Betsize::Size33,
-            Betsize::Size50,
-            Betsize::Size75,
-            Betsize::Size150,
-        ];
-    }
+    let data_dir = config::resolve_data_dir(data_dir);
+    let cache_dir = use_cache.then(|| cache::get_cache_dir(&data_dir));
 
-    Args {
+    Ok(Args {
+        data_dir,
         positions,
         betsizes,
         heights,
         suits,
+        flush_draws,
         connections,
+        connectivity,
         pair,
-        // Only flop from the perspective of IP after OOP check is currently supported
-        actions: vec![Action::Check],
+        turn_cards,
+        high_cards,
+        second_cards,
+        ranks,
+        pair_positions,
+        patterns,
+        filter,
+        actions,
+        pot,
+        weighting,
+        board_set_policy,
+        ingestion,
+        cache_dir,
+    })
+}
+
+// Options without filters for the spot BTN vs BB after a check, tests override what they need
+#[cfg(test)]
+impl Args {
+    pub fn for_test(data_dir: impl Into<PathBuf>) -> Args {
+        Args {
+            data_dir: data_dir.into(),
+            positions: Positions::new(Position::Btn, Position::BB).unwrap(),
+            betsizes: Vec::new(),
+            heights: Vec::new(),
+            suits: Vec::new(),
+            flush_draws: Vec::new(),
+            connections: Vec::new(),
+            connectivity: Vec::new(),
+            pair: Vec::new(),
+            turn_cards: Vec::new(),
+            high_cards: Vec::new(),
+            second_cards: Vec::new(),
+            ranks: Vec::new(),
+            pair_positions: Vec::new(),
+            patterns: Vec::new(),
+            filter: None,
+            actions: vec![Action::Check],
+            pot: None,
+            weighting: Weighting::Combinatorial,
+            board_set_policy: BoardSetPolicy::Abort,
+            ingestion: Ingestion::Strict,
+            cache_dir: None,
+        }
     }
 }
 
@@ -116,24 +366,28 @@ fn parse_args(args: impl Iterator<Item = String>) -> Args {
 mod tests {
     use super::*;
 
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
     #[test]
     fn test_args() {
         let args_vec = vec![
             "-po", "BTN", "BB", "-b", "33", "75", "150", "-h", "3BW", "2BW", "-s", "M", "-c", "DC",
-            "GS", "OESD", "WH", "-pa", "U",
+            "GS", "OESD", "WH", "-pa", "U", "-fd", "A", "nut:k",
         ]
         .into_iter()
         .map(|arg| arg.to_string());
 
-        let args = parse_args(args_vec);
+        let args = parse_args(args_vec).unwrap();
 
         assert_eq!(args.positions.ip, Position::Btn);
         assert_eq!(args.positions.oop, Position::BB);
 
         assert_eq!(args.betsizes.len(), 3);
-        assert!(args.betsizes.contains(&Betsize::Size33));
-        assert!(args.betsizes.contains(&Betsize::Size75));
-        assert!(args.betsizes.contains(&Betsize::Size150));
+        assert!(args.betsizes.contains(&Betsize::Percent(33)));
+        assert!(args.betsizes.contains(&Betsize::Percent(75)));
+        assert!(args.betsizes.contains(&Betsize::Percent(150)));
 
         assert_eq!(args.heights.len(), 2);
         assert!(args.heights.contains(&BoardHeight::TripleBW));
@@ -142,6 +396,11 @@ mod tests {
         assert_eq!(args.suits.len(), 1);
         assert!(args.suits.contains(&BoardSuit::Montone));
 
+        assert_eq!(
+            args.flush_draws,
+            vec![FlushDraw::AceHigh, FlushDraw::Nut(Rank::K)]
+        );
+
         assert_eq!(args.connections.len(), 4);
         assert!(args.connections.contains(&Connection::Disconnected));
         assert!(args.connections.contains(&Connection::Gutshot));
@@ -151,4 +410,285 @@ mod tests {
         assert_eq!(args.pair.len(), 1);
         assert!(args.pair.contains(&BoardPair::Unpaired));
     }
+
+    #[test]
+    fn test_args_turn_cards() {
+        let args_vec = vec!["-po", "BTN", "BB", "-t", "O", "ST"]
+            .into_iter()
+            .map(|arg| arg.to_string());
+
+        let args = parse_args(args_vec).unwrap();
+
+        assert_eq!(
+            args.turn_cards,
+            vec![TurnCard::Overcard, TurnCard::StraightCompleting]
+        );
+    }
+
+    #[test]
+    fn test_args_ranks() {
+        let args = parse_args(
+            to_args(&[
+                "-po", "BTN", "BB", "-hc", "a", "t-q", "-sc", "9-", "-r", "K", "-pp", "high",
+            ])
+            .into_iter(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            args.high_cards,
+            vec![
+                RankRange::from_str("A").unwrap(),
+                RankRange::from_str("T-Q").unwrap()
+            ]
+        );
+        assert_eq!(args.second_cards, vec![RankRange::from_str("9-").unwrap()]);
+        assert_eq!(args.ranks, vec![Rank::K]);
+        assert_eq!(args.pair_positions, vec![PairPosition::High]);
+
+        assert!(parse_args(to_args(&["-po", "BTN", "BB", "-r", "AK"]).into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_args_connectivity() {
+        let args = parse_args(
+            ["-po", "BTN", "BB", "-cm", "sh:10+", "GAP:3-"]
+                .into_iter()
+                .map(|arg| arg.to_string()),
+        )
+        .unwrap();
+
+        assert_eq!(
+            args.connectivity,
+            vec![
+                ConnectivityRange::from_str("SH:10+").unwrap(),
+                ConnectivityRange::from_str("GAP:3-").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_args_patterns() {
+        let args =
+            parse_args(to_args(&["-po", "BTN", "BB", "-bp", "A[KQJ]x", "Kxx:ssy"]).into_iter())
+                .unwrap();
+        assert_eq!(
+            args.patterns,
+            vec![
+                BoardPattern::from_str("A[KQJ]x").unwrap(),
+                BoardPattern::from_str("Kxx:ssy").unwrap()
+            ]
+        );
+
+        assert!(parse_args(to_args(&["-po", "BTN", "BB", "-bp", "A[KQ"]).into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_args_filter() {
+        let args = parse_args(to_args(&["-po", "BTN", "BB"]).into_iter()).unwrap();
+        assert_eq!(args.filter, None);
+
+        let args = parse_args(
+            to_args(&["-po", "BTN", "BB", "-f", "(h:2BW or h:3BW) and not s:M"]).into_iter(),
+        )
+        .unwrap();
+        assert_eq!(
+            args.filter,
+            Some(Expression::from_str("(h:2BW or h:3BW) and not s:M").unwrap())
+        );
+
+        let err =
+            parse_args(to_args(&["-po", "BTN", "BB", "-f", "h:2BW", "or", "h:3BW"]).into_iter())
+                .err()
+                .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Invalid arguments: unexpected argument: or"
+        );
+
+        let err = parse_args(to_args(&["-po", "BTN", "BB", "-f", "h:2BW or"]).into_iter())
+            .err()
+            .unwrap();
+        assert!(err
+            .to_string()
+            .starts_with("Invalid arguments: expected a condition in filter expression"));
+    }
+
+    #[test]
+    fn test_args_actions() {
+        let args = parse_args(to_args(&["-po", "BTN", "BB"]).into_iter()).unwrap();
+        assert_eq!(args.actions, vec![Action::Check]);
+
+        let args = parse_args(to_args(&["-po", "BTN", "BB", "-a", "X-B"]).into_iter()).unwrap();
+        assert_eq!(args.actions, vec![Action::Check, Action::Bet]);
+
+        let args = parse_args(to_args(&["-a", "root", "-po", "BTN", "BB"]).into_iter()).unwrap();
+        assert!(args.actions.is_empty());
+    }
+
+    #[test]
+    fn test_args_weighting() {
+        let args = parse_args(to_args(&["-po", "BTN", "BB"]).into_iter()).unwrap();
+        assert_eq!(args.weighting, Weighting::Combinatorial);
+
+        let args = parse_args(to_args(&["-po", "BTN", "BB", "-w", "U"]).into_iter()).unwrap();
+        assert_eq!(args.weighting, Weighting::Uniform);
+    }
+
+    #[test]
+    fn test_args_board_set_policy() {
+        let args = parse_args(to_args(&["-po", "BTN", "BB"]).into_iter()).unwrap();
+        assert_eq!(args.board_set_policy, BoardSetPolicy::Abort);
+
+        let args = parse_args(to_args(&["-po", "BTN", "BB", "-m", "shared"]).into_iter()).unwrap();
+        assert_eq!(args.board_set_policy, BoardSetPolicy::Shared);
+    }
+
+    #[test]
+    fn test_args_ingestion() {
+        let args = parse_args(to_args(&["-po", "BTN", "BB"]).into_iter()).unwrap();
+        assert_eq!(args.ingestion, Ingestion::Strict);
+
+        let args = parse_args(to_args(&["-po", "BTN", "BB", "-i", "lenient"]).into_iter()).unwrap();
+        assert_eq!(args.ingestion, Ingestion::Lenient);
+    }
+
+    #[test]
+    fn test_args_pot() {
+        let args = parse_args(to_args(&["-po", "BTN", "BB"]).into_iter()).unwrap();
+        assert_eq!(args.pot, None);
+
+        let args = parse_args(to_args(&["-po", "BTN", "BB", "-pot", "55"]).into_iter()).unwrap();
+        assert_eq!(args.pot, Some(55.0));
+
+        assert!(parse_args(to_args(&["-po", "BTN", "BB", "-pot", "0"]).into_iter()).is_err());
+        assert!(parse_args(to_args(&["-po", "BTN", "BB", "-pot", "x"]).into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_args_data_dir() {
+        let args_vec = vec!["-po", "BTN", "BB", "-d", "/mnt/Solves/GTO"]
+            .into_iter()
+            .map(|arg| arg.to_string());
+
+        let args = parse_args(args_vec).unwrap();
+
+        assert_eq!(args.data_dir, PathBuf::from("/mnt/Solves/GTO"));
+    }
+
+    #[test]
+    fn test_parse_command() {
+        let list_args = vec!["list", "-d", "./test_data"]
+            .into_iter()
+            .map(|arg| arg.to_string());
+
+        match parse_command(list_args).unwrap() {
+            Command::List(data_dir) => assert_eq!(data_dir, PathBuf::from("./test_data")),
+            _ => panic!("Expected list command"),
+        }
+
+        let aggregate_args = vec!["-po", "CO", "BB"]
+            .into_iter()
+            .map(|arg| arg.to_string());
+
+        match parse_command(aggregate_args).unwrap() {
+            Command::Aggregate(args) => assert_eq!(args.positions.ip, Position::CO),
+            _ => panic!("Expected aggregate command"),
+        }
+
+        let coverage_args = vec!["coverage", "-po", "BTN", "BB"]
+            .into_iter()
+            .map(|arg| arg.to_string());
+
+        match parse_command(coverage_args).unwrap() {
+            Command::Coverage(args) => assert_eq!(args.positions.ip, Position::Btn),
+            _ => panic!("Expected coverage command"),
+        }
+
+        let cache_args = vec!["cache", "rebuild", "-d", "./test_data"]
+            .into_iter()
+            .map(|arg| arg.to_string());
+
+        match parse_command(cache_args).unwrap() {
+            Command::Cache(CacheAction::Rebuild, data_dir) => {
+                assert_eq!(data_dir, PathBuf::from("./test_data"))
+            }
+            _ => panic!("Expected cache rebuild command"),
+        }
+
+        assert_eq!(
+            parse_command(to_args(&["list", "-d"]).into_iter())
+                .err()
+                .unwrap()
+                .to_string(),
+            "Invalid arguments: expected a directory after -d"
+        );
+    }
+
+    #[test]
+    fn test_args_cache_dir() {
+        let args = parse_args(to_args(&["-po", "BTN", "BB", "-d", "/mnt/solves"]).into_iter());
+        assert_eq!(
+            args.unwrap().cache_dir,
+            Some(PathBuf::from("/mnt/solves/.gto-parse-cache"))
+        );
+
+        let args = parse_args(to_args(&["-nc", "-po", "BTN", "BB"]).into_iter()).unwrap();
+        assert_eq!(args.cache_dir, None);
+    }
+
+    #[test]
+    fn test_args_positions_order() {
+        let args_vec = vec!["-po", "BB", "SB"]
+            .into_iter()
+            .map(|arg| arg.to_string());
+
+        let args = parse_args(args_vec).unwrap();
+
+        assert_eq!(args.positions.ip, Position::BB);
+        assert_eq!(args.positions.oop, Position::SB);
+    }
+
+    #[test]
+    fn test_args_betsizes() {
+        let args_vec = vec!["-po", "BTN", "BB", "-b", "10", "66", "125", "ai"]
+            .into_iter()
+            .map(|arg| arg.to_string());
+
+        let args = parse_args(args_vec).unwrap();
+
+        assert_eq!(
+            args.betsizes,
+            vec![
+                Betsize::Percent(10),
+                Betsize::Percent(66),
+                Betsize::Percent(125),
+                Betsize::AllIn,
+            ]
+        );
+
+        let args_vec = vec!["-po", "BTN", "BB"]
+            .into_iter()
+            .map(|arg| arg.to_string());
+
+        assert!(parse_args(args_vec).unwrap().betsizes.is_empty());
+    }
+
+    #[test]
+    fn test_args_errors() {
+        let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+
+        assert_eq!(
+            parse(&["-po", "BTN"]).err().unwrap().to_string(),
+            "Invalid arguments: expected two positions after -po, got 1"
+        );
+        assert_eq!(
+            parse(&["BTN", "-po", "BTN", "BB"])
+                .err()
+                .unwrap()
+                .to_string(),
+            "Invalid arguments: unexpected argument: BTN"
+        );
+        assert!(parse(&["-po", "BTN", "BB", "-b", "abc"]).is_err());
+    }
 }
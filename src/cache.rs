@@ -0,0 +1,467 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+    time::UNIX_EPOCH,
+};
+
+use crate::{
+    calculation::{
+        self,
+        datarow::{ActionFreq, DataRow},
+        ingestion::Ingestion,
+        schema::ColumnSchema,
+        LoadedReport, ParsedReport, ParsedRow,
+    },
+    catalog,
+    error::Error,
+    files,
+    poker::{board::Board, ParseError},
+};
+
+// Hidden, so the directory walkers skip it like any other dot entry of the data root
+pub const CACHE_DIR_NAME: &str = ".gto-parse-cache";
+const MAGIC: &[u8; 4] = b"GTPC";
+// Entries of other versions are treated as missing, bump it whenever the layout changes
+const VERSION: u32 = 1;
+const ENTRY_EXTENSION: &str = "bin";
+
+pub enum CacheAction {
+    Clear,
+    Rebuild,
+}
+
+impl FromStr for CacheAction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<CacheAction, ParseError> {
+        match s {
+            "CLEAR" => Ok(CacheAction::Clear),
+            "REBUILD" => Ok(CacheAction::Rebuild),
+            _ => Err(ParseError::str("cache action", s)),
+        }
+    }
+}
+
+pub struct CacheSummary {
+    pub cache_dir: PathBuf,
+    pub num_removed: usize,
+    pub num_cached: usize,
+    pub warnings: Vec<String>,
+}
+
+// Source file version an entry was built from
+#[derive(Debug, PartialEq)]
+struct Stamp {
+    size: u64,
+    modified_secs: u64,
+    modified_nanos: u32,
+}
+
+pub fn get_cache_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(CACHE_DIR_NAME)
+}
+
+// The cached report, if there is an entry for the current version of the source file
+pub fn load(cache_dir: &Path, source: &Path) -> Option<ParsedReport> {
+    let bytes = fs::read(get_entry_path(cache_dir, source)).ok()?;
+    decode(&bytes, &get_key(source), &read_stamp(source)?)
+}
+
+pub fn store(cache_dir: &Path, source: &Path, report: &ParsedReport) -> Result<(), Error> {
+    let stamp = read_stamp(source).ok_or_else(|| {
+        Error::Data(format!(
+            "Could not read size and modification time of {}",
+            source.display()
+        ))
+    })?;
+    let bytes = encode(report, &get_key(source), &stamp);
+
+    // Written aside and renamed, so concurrent runs never read half an entry
+    let entry_path = get_entry_path(cache_dir, source);
+    let tmp_path = entry_path.with_extension(format!("tmp{}", process::id()));
+    let io_error = |path: &Path| {
+        let path = path.to_path_buf();
+        move |source| Error::Io { path, source }
+    };
+
+    fs::create_dir_all(cache_dir).map_err(io_error(cache_dir))?;
+    fs::write(&tmp_path, bytes).map_err(io_error(&tmp_path))?;
+    fs::rename(&tmp_path, &entry_path).map_err(io_error(&entry_path))
+}
+
+pub fn clear(data_dir: &Path) -> Result<CacheSummary, Error> {
+    let cache_dir = get_cache_dir(data_dir);
+    let mut num_removed = 0;
+
+    if cache_dir.is_dir() {
+        num_removed = files::get_files(&cache_dir)?.len();
+        fs::remove_dir_all(&cache_dir).map_err(|source| Error::Io {
+            path: cache_dir.clone(),
+            source,
+        })?;
+    }
+
+    Ok(CacheSummary {
+        cache_dir,
+        num_removed,
+        num_cached: 0,
+        warnings: Vec::new(),
+    })
+}
+
+// Clears the cache and parses every action file of the data root into it
+pub fn rebuild(data_dir: &Path) -> Result<CacheSummary, Error> {
+    let mut summary = clear(data_dir)?;
+
+    for spot_dir in files::get_dirs(data_dir)? {
+        for size_dir in files::get_dirs(&spot_dir.path())? {
+            for file in files::get_files(&size_dir.path())? {
+                let path = file.path();
+                if catalog::parse_action_file_name(&files::get_name(&path)).is_none() {
+                    continue;
+                }
+
+                match calculation::load_report(&path, &Ingestion::Lenient, Some(&summary.cache_dir))
+                {
+                    Ok(LoadedReport {
+                        skipped: Some(skipped),
+                        ..
+                    }) => summary.warnings.push(format!("{skipped}, not cached")),
+                    Ok(LoadedReport {
+                        cache_error: Some(err),
+                        ..
+                    })
+                    | Err(err) => summary.warnings.push(format!("Not cached: {err}")),
+                    Ok(_) => summary.num_cached += 1,
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+// Spot, size and file name identify a report within its data root
+fn get_key(source: &Path) -> String {
+    let components: Vec<String> = source
+        .components()
+        .rev()
+        .take(3)
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+
+    components.into_iter().rev().collect::<Vec<_>>().join("/")
+}
+
+// The key is stored in the entry too, so colliding hashes only cost a cache miss
+fn get_entry_path(cache_dir: &Path, source: &Path) -> PathBuf {
+    cache_dir
+        .join(format!("{:016x}", fnv1a(get_key(source).as_bytes())))
+        .with_extension(ENTRY_EXTENSION)
+}
+
+// 64 bit FNV-1a, fixed unlike the std hashers, so entry names outlive toolchain updates
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn read_stamp(source: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(source).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+
+    Some(Stamp {
+        size: metadata.len(),
+        modified_secs: modified.as_secs(),
+        modified_nanos: modified.subsec_nanos(),
+    })
+}
+
+/*
+ * Layout, all numbers little endian:
+ * magic, version, key, source size, modification secs and nanos, header, number of rows
+ * of the file, then per parsed row its dealt cards, equity, EV, optional weight and the
+ * frequencies in the order of the header's action columns.
+ */
+fn encode(report: &ParsedReport, key: &str, stamp: &Stamp) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    write_str(&mut bytes, key);
+    bytes.extend_from_slice(&stamp.size.to_le_bytes());
+    bytes.extend_from_slice(&stamp.modified_secs.to_le_bytes());
+    bytes.extend_from_slice(&stamp.modified_nanos.to_le_bytes());
+    write_str(&mut bytes, &report.schema.names.join("\t"));
+    bytes.extend_from_slice(&(report.num_rows as u32).to_le_bytes());
+    bytes.extend_from_slice(&(report.rows.len() as u32).to_le_bytes());
+
+    for row in &report.rows {
+        write_str(&mut bytes, &get_dealt_cards(&row.board));
+        bytes.extend_from_slice(&row.data_row.eq.to_le_bytes());
+        bytes.extend_from_slice(&row.data_row.ev.to_le_bytes());
+
+        match row.weight {
+            Some(weight) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&weight.to_le_bytes());
+            }
+            None => bytes.push(0),
+        }
+
+        for freq in &row.data_row.freqs {
+            bytes.extend_from_slice(&freq.freq.to_le_bytes());
+        }
+    }
+
+    bytes
+}
+
+fn decode(bytes: &[u8], key: &str, stamp: &Stamp) -> Option<ParsedReport> {
+    let mut reader = Reader { bytes };
+
+    if reader.take(MAGIC.len())? != MAGIC || reader.read_u32()? != VERSION {
+        return None;
+    }
+    if reader.read_str()? != key {
+        return None;
+    }
+
+    let entry_stamp = Stamp {
+        size: reader.read_u64()?,
+        modified_secs: reader.read_u64()?,
+        modified_nanos: reader.read_u32()?,
+    };
+    if entry_stamp != *stamp {
+        return None;
+    }
+
+    let schema = ColumnSchema::parse(&reader.read_str()?).ok()?;
+    let num_rows = reader.read_u32()? as usize;
+    let num_parsed_rows = reader.read_u32()? as usize;
+
+    let rows = (0..num_parsed_rows)
+        .map(|_| read_row(&mut reader, &schema))
+        .collect::<Option<_>>()?;
+
+    reader.bytes.is_empty().then_some(ParsedReport {
+        schema,
+        num_rows,
+        rows,
+    })
+}
+
+fn read_row(reader: &mut Reader, schema: &ColumnSchema) -> Option<ParsedRow> {
+    let board = Board::try_from(&reader.read_str()?[..]).ok()?;
+    let eq = reader.read_f32()?;
+    let ev = reader.read_f32()?;
+    let weight = match reader.take(1)?[0] {
+        0 => None,
+        _ => Some(reader.read_f32()?),
+    };
+
+    let freqs = schema
+        .actions
+        .iter()
+        .map(|column| {
+            Some(ActionFreq {
+                action: column.action.clone(),
+                chips: column.chips,
+                freq: reader.read_f32()?,
+            })
+        })
+        .collect::<Option<_>>()?;
+
+    Some(ParsedRow {
+        board,
+        data_row: DataRow {
+            size: None,
+            eq,
+            ev,
+            freqs,
+        },
+        weight,
+    })
+}
+
+// Flop, turn and river in the order they were dealt, which the board's display does not keep
+fn get_dealt_cards(board: &Board) -> String {
+    board
+        .get_flop()
+        .cards()
+        .chain(board.turn)
+        .chain(board.river)
+        .map(|card| card.to_string())
+        .collect()
+}
+
+fn write_str(bytes: &mut Vec<u8>, s: &str) {
+    bytes.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(s.as_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn read_u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn read_f32(&mut self) -> Option<f32> {
+        Some(f32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn read_str(&mut self) -> Option<String> {
+        let len = self.read_u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::files::TestDir;
+
+    use super::*;
+
+    const HEADER: &str = "Tree\tEquity(*)\tEV\tBet 18.15\tCheck\n";
+
+    fn stamp() -> Stamp {
+        Stamp {
+            size: 120,
+            modified_secs: 1_700_000_000,
+            modified_nanos: 5,
+        }
+    }
+
+    fn report() -> ParsedReport {
+        let schema = ColumnSchema::parse("Tree\tWeight\tEquity(*)\tEV\tBet 18.15\tCheck").unwrap();
+        let rows = [
+            "Ks7d4c2h\t0.5\t50\t30\t20\t80",
+            "8s8d8c\t1\t48.5\t36.25\t81.5\t18.5",
+        ]
+        .iter()
+        .map(|line| {
+            let cells: Vec<&str> = line.split('\t').collect();
+
+            ParsedRow {
+                board: Board::try_from(cells[0]).unwrap(),
+                data_row: DataRow::new(&cells, &schema).unwrap(),
+                weight: calculation::weight::read_weight_column(&cells, &schema).unwrap(),
+            }
+        })
+        .collect();
+
+        ParsedReport {
+            schema,
+            num_rows: 3,
+            rows,
+        }
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let report = report();
+        let bytes = encode(&report, "BTN vs BB/33/after_check.txt", &stamp());
+
+        let decoded = decode(&bytes, "BTN vs BB/33/after_check.txt", &stamp()).unwrap();
+
+        assert_eq!(decoded.schema, report.schema);
+        assert_eq!(decoded.num_rows, 3);
+        assert_eq!(decoded.rows.len(), 2);
+        for (decoded_row, row) in decoded.rows.iter().zip(&report.rows) {
+            assert_eq!(decoded_row.board, row.board);
+            assert_eq!(decoded_row.data_row, row.data_row);
+            assert_eq!(decoded_row.weight, row.weight);
+        }
+        assert_eq!(decoded.rows[0].board.turn, report.rows[0].board.turn);
+    }
+
+    #[test]
+    fn test_decode_stale_or_corrupt() {
+        let report = report();
+        let bytes = encode(&report, "BTN vs BB/33/after_check.txt", &stamp());
+
+        let modified = Stamp {
+            modified_secs: 1_700_000_001,
+            ..stamp()
+        };
+        assert!(decode(&bytes, "BTN vs BB/33/after_check.txt", &modified).is_none());
+        assert!(decode(&bytes, "BTN vs BB/50/after_check.txt", &stamp()).is_none());
+        assert!(decode(
+            &bytes[..bytes.len() - 1],
+            "BTN vs BB/33/after_check.txt",
+            &stamp()
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_get_key() {
+        assert_eq!(
+            get_key(Path::new("/mnt/solves/BTN vs BB/33/after_check.txt")),
+            "BTN vs BB/33/after_check.txt"
+        );
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn test_load_store_rebuild_clear() {
+        let test_dir = TestDir::new("cache");
+        let data_dir = test_dir.path();
+        let source = data_dir.join("BTN vs BB/33/after_check.txt");
+        let cache_dir = get_cache_dir(data_dir);
+        fs::create_dir_all(data_dir.join("BTN vs BB/33")).unwrap();
+        fs::create_dir_all(data_dir.join("BTN vs BB/50")).unwrap();
+        fs::write(&source, format!("{HEADER}Ks7d4c\t50\t30\t20\t80\n")).unwrap();
+        fs::write(
+            data_dir.join("BTN vs BB/50/after_check.txt"),
+            format!("{HEADER}Ks7d4c\t50\t-\t20\t80\n"),
+        )
+        .unwrap();
+
+        let summary = rebuild(data_dir).unwrap();
+        assert_eq!(summary.num_cached, 1);
+        assert_eq!(summary.warnings.len(), 1);
+        assert!(summary.warnings[0].ends_with("(lines 2), not cached"));
+
+        let cached = load(&cache_dir, &source).unwrap();
+        assert_eq!(cached.rows[0].data_row.ev, 30.0);
+
+        // A changed source invalidates its entry
+        fs::write(
+            &source,
+            format!("{HEADER}Ks7d4c\t50\t31\t20\t80\n8s8d8c\t50\t30\t20\t80\n"),
+        )
+        .unwrap();
+        assert!(load(&cache_dir, &source).is_none());
+
+        let summary = clear(data_dir).unwrap();
+        assert_eq!(summary.num_removed, 1);
+        assert!(!cache_dir.exists());
+    }
+}
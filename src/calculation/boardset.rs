@@ -0,0 +1,141 @@
+use std::{collections::HashSet, str::FromStr};
+
+use crate::poker::{betsize::Betsize, board::Board, ParseError};
+
+// What to do when the size files of a spot do not hold the same boards
+#[derive(Debug, PartialEq)]
+pub enum BoardSetPolicy {
+    Abort,
+    Shared,
+    Own,
+}
+
+impl FromStr for BoardSetPolicy {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<BoardSetPolicy, ParseError> {
+        match s {
+            "ABORT" => Ok(BoardSetPolicy::Abort),
+            "SHARED" => Ok(BoardSetPolicy::Shared),
+            "OWN" => Ok(BoardSetPolicy::Own),
+            _ => Err(ParseError::str("board set policy", s)),
+        }
+    }
+}
+
+/*
+ * One line per board that is missing in some size files, naming the sizes holding it.
+ * Boards are compared by their canonical form, so exports may name them differently.
+ */
+pub fn find_mismatches(board_sets: &[(&Betsize, &[Board])]) -> Vec<String> {
+    let canonical_sets: Vec<HashSet<Board>> = board_sets
+        .iter()
+        .map(|(_, boards)| boards.iter().map(Board::canonical).collect())
+        .collect();
+
+    get_all_boards(board_sets)
+        .into_iter()
+        .filter_map(|board| {
+            let canonical = board.canonical();
+            let sizes: Vec<String> = board_sets
+                .iter()
+                .zip(&canonical_sets)
+                .filter(|(_, canonical_set)| canonical_set.contains(&canonical))
+                .map(|((betsize, _), _)| betsize.to_string())
+                .collect();
+
+            (sizes.len() < board_sets.len())
+                .then(|| format!("Board {board} only in sizes {}", sizes.join(", ")))
+        })
+        .collect()
+}
+
+// Canonical forms of the boards present in every size file
+pub fn get_shared(board_sets: &[(&Betsize, &[Board])]) -> HashSet<Board> {
+    board_sets
+        .iter()
+        .map(|(_, boards)| boards.iter().map(Board::canonical).collect::<HashSet<_>>())
+        .reduce(|shared, canonical_set| &shared & &canonical_set)
+        .unwrap_or_default()
+}
+
+// Every board of any size file once, in the order they first appear
+pub fn get_all_boards(board_sets: &[(&Betsize, &[Board])]) -> Vec<Board> {
+    let mut seen: HashSet<Board> = HashSet::new();
+
+    board_sets
+        .iter()
+        .flat_map(|(_, boards)| boards.iter())
+        .filter(|board| seen.insert(board.canonical()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boards(boards: &[&str]) -> Vec<Board> {
+        boards
+            .iter()
+            .map(|board| Board::try_from(*board).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_board_set_policy_from_str() {
+        assert_eq!(
+            BoardSetPolicy::from_str("ABORT").unwrap(),
+            BoardSetPolicy::Abort
+        );
+        assert_eq!(
+            BoardSetPolicy::from_str("SHARED").unwrap(),
+            BoardSetPolicy::Shared
+        );
+        assert_eq!(
+            BoardSetPolicy::from_str("OWN").unwrap(),
+            BoardSetPolicy::Own
+        );
+        assert!(BoardSetPolicy::from_str("X").is_err());
+    }
+
+    #[test]
+    fn test_identical_isomorphic_board_sets() {
+        let boards_33 = boards(&["KsQs2d", "8d8c8s"]);
+        let boards_75 = boards(&["8h8d8c", "KhQh2c"]);
+        let board_sets = [
+            (&Betsize::Percent(33), &boards_33[..]),
+            (&Betsize::Percent(75), &boards_75[..]),
+        ];
+
+        assert!(find_mismatches(&board_sets).is_empty());
+        assert_eq!(get_shared(&board_sets).len(), 2);
+        assert_eq!(get_all_boards(&board_sets), boards_33);
+    }
+
+    #[test]
+    fn test_mismatched_board_sets() {
+        let boards_33 = boards(&["KsQs2d", "8d8c8s", "As7d4c"]);
+        let boards_50 = boards(&["KsQs2d", "8d8c8s"]);
+        let boards_75 = boards(&["KsQs2d", "Ts6s4d"]);
+        let board_sets = [
+            (&Betsize::Percent(33), &boards_33[..]),
+            (&Betsize::Percent(50), &boards_50[..]),
+            (&Betsize::Percent(75), &boards_75[..]),
+        ];
+
+        assert_eq!(
+            find_mismatches(&board_sets),
+            vec![
+                "Board 8d8c8s only in sizes 33, 50",
+                "Board As7d4c only in sizes 33",
+                "Board Ts6s4d only in sizes 75",
+            ]
+        );
+        assert_eq!(
+            get_shared(&board_sets),
+            HashSet::from([Board::try_from("KsQs2d").unwrap().canonical()])
+        );
+        assert_eq!(get_all_boards(&board_sets).len(), 4);
+    }
+}
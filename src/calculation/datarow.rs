@@ -1,30 +1,64 @@
-use std::ops::{Add, Div};
+use std::ops::{Add, Div, Mul};
 
-use crate::poker::{betsize::Betsize, board::Board};
+use crate::poker::{action::Action, betsize::Betsize};
+
+use super::schema::{ColumnSchema, SchemaError};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActionFreq {
+    pub action: Action,
+    pub chips: Option<f32>,
+    pub freq: f32,
+}
 
 #[derive(Debug, PartialEq)]
 pub struct DataRow {
     pub size: Option<Betsize>,
     pub eq: f32,
     pub ev: f32,
-    pub bet_freq: f32,
-    pub check_freq: f32,
+    pub freqs: Vec<ActionFreq>,
 }
 
 impl DataRow {
-    pub fn new(line: &str) -> DataRow {
-        let split: Vec<&str> = line.split('\t').collect();
-
-        assert!(split.len() >= 5);
-        Board::try_from(split[0]).expect("Expected first part of line to be a valid board");
+    // Numbers of the cells of a report line, whose board is parsed by the caller
+    pub fn new(cells: &[&str], schema: &ColumnSchema) -> Result<DataRow, SchemaError> {
+        let freqs = schema
+            .actions
+            .iter()
+            .map(|column| {
+                Ok(ActionFreq {
+                    action: column.action.clone(),
+                    chips: column.chips,
+                    freq: schema.number(cells, column.index)?,
+                })
+            })
+            .collect::<Result<_, SchemaError>>()?;
 
-        DataRow {
+        Ok(DataRow {
             size: None,
-            eq: split[1].parse().expect("Equity needs to be a number"),
-            ev: split[2].parse().expect("EV needs to be a number"),
-            bet_freq: split[3].parse().expect("Bet freq. needs to be a number"),
-            check_freq: split[4].parse().expect("Check freq. needs to be a number"),
-        }
+            eq: schema.number(cells, schema.equity)?,
+            ev: schema.number(cells, schema.ev)?,
+            freqs,
+        })
+    }
+
+    pub fn freq(&self, action: &Action) -> f32 {
+        self.freqs
+            .iter()
+            .filter(|freq| freq.action == *action)
+            .map(|freq| freq.freq)
+            .sum()
+    }
+
+    // Equity and EV of the other player, as both share the pot
+    pub fn switch_to_oop(&mut self, pot: f32) {
+        self.eq = 100.0 - self.eq;
+        self.ev = pot - self.ev;
+    }
+
+    // Bets and raises of every size combined
+    pub fn any_bet_freq(&self) -> f32 {
+        self.freq(&Action::Bet) + self.freq(&Action::Raise)
     }
 }
 
@@ -33,69 +67,230 @@ impl Add for DataRow {
 
     fn add(self, other: DataRow) -> DataRow {
         assert_eq!(self.size, other.size);
+        assert_eq!(self.freqs.len(), other.freqs.len());
+
+        let freqs = self
+            .freqs
+            .into_iter()
+            .zip(other.freqs)
+            .map(|(freq1, freq2)| {
+                assert_eq!(freq1.action, freq2.action);
+                assert_eq!(freq1.chips, freq2.chips);
+
+                ActionFreq {
+                    freq: freq1.freq + freq2.freq,
+                    ..freq1
+                }
+            })
+            .collect();
 
         DataRow {
             size: self.size,
             eq: self.eq + other.eq,
             ev: self.ev + other.ev,
-            bet_freq: self.bet_freq + other.bet_freq,
-            check_freq: self.check_freq + other.check_freq,
+            freqs,
+        }
+    }
+}
+
+// Scales a row by its weight, so a sum of weighted rows divided by the total weight is their weighted mean
+impl Mul<f32> for DataRow {
+    type Output = DataRow;
+
+    fn mul(self, factor: f32) -> DataRow {
+        let freqs = self
+            .freqs
+            .into_iter()
+            .map(|freq| ActionFreq {
+                freq: freq.freq * factor,
+                ..freq
+            })
+            .collect();
+
+        DataRow {
+            size: self.size,
+            eq: self.eq * factor,
+            ev: self.ev * factor,
+            freqs,
         }
     }
 }
 
-impl Div<usize> for DataRow {
+impl Div<f32> for DataRow {
     type Output = DataRow;
 
-    fn div(self, divisor: usize) -> DataRow {
-        assert_ne!(divisor, 0);
+    fn div(self, divisor: f32) -> DataRow {
+        assert_ne!(divisor, 0.0);
 
-        let divisor = divisor as f32;
+        let freqs = self
+            .freqs
+            .into_iter()
+            .map(|freq| ActionFreq {
+                freq: freq.freq / divisor,
+                ..freq
+            })
+            .collect();
 
         DataRow {
             size: self.size,
             eq: self.eq / divisor,
             ev: self.ev / divisor,
-            bet_freq: self.bet_freq / divisor,
-            check_freq: self.check_freq / divisor,
+            freqs,
         }
     }
 }
 
+#[cfg(test)]
+pub fn bet_check_freqs(chips: f32, bet_freq: f32, check_freq: f32) -> Vec<ActionFreq> {
+    vec![
+        ActionFreq {
+            action: Action::Bet,
+            chips: Some(chips),
+            freq: bet_freq,
+        },
+        ActionFreq {
+            action: Action::Check,
+            chips: None,
+            freq: check_freq,
+        },
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const HEADER: &str = "Tree	Equity(*)	EV	Bet 18.15	Check";
+
+    fn parse(line: &str, schema: &ColumnSchema) -> Result<DataRow, SchemaError> {
+        DataRow::new(&line.split('\t').collect::<Vec<_>>(), schema)
+    }
+
+    fn new_datarow(line: &str) -> DataRow {
+        parse(line, &ColumnSchema::parse(HEADER).unwrap()).unwrap()
+    }
+
     #[test]
     fn test_datarow_new() {
-        let datarow = DataRow::new("8s8d8c	56.532	35.471	69.566	30.434");
+        let datarow = new_datarow("8s8d8c	56.532	35.471	69.566	30.434");
 
         assert_eq!(datarow.eq, 56.532);
         assert_eq!(datarow.ev, 35.471);
-        assert_eq!(datarow.bet_freq, 69.566);
-        assert_eq!(datarow.check_freq, 30.434);
+        assert_eq!(datarow.freq(&Action::Bet), 69.566);
+        assert_eq!(datarow.freq(&Action::Check), 30.434);
     }
 
     #[test]
     fn test_datarow_add() {
-        let datarow1 = DataRow::new("8s8d8c	56.532	35.471	69.566	30.434");
-        let datarow2 = DataRow::new("8s5s5d	54.398	28.831	6.355	93.645");
+        let datarow1 = new_datarow("8s8d8c	56.532	35.471	69.566	30.434");
+        let datarow2 = new_datarow("8s5s5d	54.398	28.831	6.355	93.645");
         let added_datarow = datarow1 + datarow2;
 
         assert_eq!(added_datarow.eq, 56.532 + 54.398);
         assert_eq!(added_datarow.ev, 35.471 + 28.831);
-        assert_eq!(added_datarow.bet_freq, 69.566 + 6.355);
-        assert_eq!(added_datarow.check_freq, 30.434 + 93.645);
+        assert_eq!(added_datarow.freq(&Action::Bet), 69.566 + 6.355);
+        assert_eq!(added_datarow.freq(&Action::Check), 30.434 + 93.645);
     }
 
     #[test]
     fn test_datarow_div() {
-        let datarow = DataRow::new("8s8d8c	110.93	64.302	75.921	124.079");
-        let divided_by_2 = datarow / 2;
+        let datarow = new_datarow("8s8d8c	110.93	64.302	75.921	124.079");
+        let divided_by_2 = datarow / 2.0;
 
         assert_eq!(divided_by_2.eq, 110.93 / 2.0);
         assert_eq!(divided_by_2.ev, 64.302 / 2.0);
-        assert_eq!(divided_by_2.bet_freq, 75.921 / 2.0);
-        assert_eq!(divided_by_2.check_freq, 124.079 / 2.0);
+        assert_eq!(divided_by_2.freq(&Action::Bet), 75.921 / 2.0);
+        assert_eq!(divided_by_2.freq(&Action::Check), 124.079 / 2.0);
+    }
+
+    #[test]
+    fn test_datarow_weighted_mean() {
+        let datarow1 = new_datarow("8s8d8c	56	35	70	30");
+        let datarow2 = new_datarow("Ks7d4c	60	20	10	90");
+        let datarow = (datarow1 * 4.0 + datarow2 * 24.0) / 28.0;
+
+        assert_eq!(datarow.eq, 59.42857);
+        assert_eq!(datarow.ev, 22.142857);
+        assert_eq!(datarow.freq(&Action::Bet), 18.571428);
+        assert_eq!(datarow.freq(&Action::Check), 81.42857);
+    }
+
+    #[test]
+    fn test_datarow_new_by_column_name() {
+        let schema = ColumnSchema::parse("Tree	Check	Bet 27.5	EV	Equity(*)	Total").unwrap();
+        let datarow = parse("8s8d8c	30.434	69.566	35.471	56.532	100", &schema).unwrap();
+
+        assert_eq!(datarow.eq, 56.532);
+        assert_eq!(datarow.ev, 35.471);
+        assert_eq!(datarow.freq(&Action::Bet), 69.566);
+        assert_eq!(datarow.freq(&Action::Check), 30.434);
+    }
+
+    #[test]
+    fn test_datarow_new_invalid() {
+        let schema = ColumnSchema::parse(HEADER).unwrap();
+
+        assert_eq!(
+            parse("8s8d8c	56.532	35.471	69.566", &schema),
+            Err(SchemaError::MissingCell("Check".to_string()))
+        );
+        assert_eq!(
+            parse("8s8d8c	56.532	-	69.566	30.434", &schema),
+            Err(SchemaError::InvalidCell {
+                column: "EV".to_string(),
+                value: "-".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_datarow_several_bets() {
+        let schema =
+            ColumnSchema::parse("Tree	Equity(*)	EV	Bet 18.15	Bet 41.25	Bet 82.5	Check").unwrap();
+        let datarow1 = parse("Ks7d4c	37.611	30.190	10.5	8.25	4.5	76.75", &schema).unwrap();
+        let datarow2 = parse("As7d4c	50.45	53.898	20.5	6.25	1.5	71.75", &schema).unwrap();
+
+        let datarow = (datarow1 + datarow2) / 2.0;
+
+        let freqs: Vec<_> = datarow
+            .freqs
+            .iter()
+            .map(|freq| (freq.chips, freq.freq))
+            .collect();
+        assert_eq!(
+            freqs,
+            vec![
+                (Some(18.15), 15.5),
+                (Some(41.25), 7.25),
+                (Some(82.5), 3.0),
+                (None, 74.25)
+            ]
+        );
+        assert_eq!(datarow.freq(&Action::Bet), 25.75);
+        assert_eq!(datarow.any_bet_freq(), 25.75);
+    }
+
+    #[test]
+    fn test_datarow_facing_bet() {
+        let schema = ColumnSchema::parse("Tree	Equity(*)	EV	Raise 60	Call	Fold").unwrap();
+        let datarow = parse("Ks7d4c	37.611	30.190	12.5	55.5	32", &schema).unwrap();
+
+        assert_eq!(datarow.freq(&Action::Raise), 12.5);
+        assert_eq!(datarow.freq(&Action::Call), 55.5);
+        assert_eq!(datarow.freq(&Action::Fold), 32.0);
+        assert_eq!(datarow.freq(&Action::Check), 0.0);
+        assert_eq!(datarow.any_bet_freq(), 12.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_datarow_add_different_actions() {
+        let schema1 = ColumnSchema::parse("Tree	Equity(*)	EV	Bet 18.15	Check").unwrap();
+        let schema2 = ColumnSchema::parse("Tree	Equity(*)	EV	Bet 27.5	Check").unwrap();
+
+        let datarow1 = parse("8s8d8c	56.532	35.471	69.566	30.434", &schema1).unwrap();
+        let datarow2 = parse("8s5s5d	54.398	28.831	6.355	93.645", &schema2).unwrap();
+
+        let _ = datarow1 + datarow2;
     }
 }
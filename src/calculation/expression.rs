@@ -0,0 +1,492 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{
+    error::Error,
+    poker::{
+        board::{
+            connection::Connection,
+            connectivity::ConnectivityRange,
+            flush::FlushDraw,
+            height::BoardHeight,
+            pair::{BoardPair, PairPosition},
+            pattern::BoardPattern,
+            suit::BoardSuit,
+            turn::TurnCard,
+            Board,
+        },
+        rank::{Rank, RankRange},
+        ParseError,
+    },
+};
+
+/*
+ * A board condition as written in a filter expression, the key of the command line
+ * option followed by one of its values, e.g. h:2BW, s:M or pa:P
+ */
+#[derive(Debug, PartialEq)]
+pub enum Condition {
+    Height(BoardHeight),
+    Suit(BoardSuit),
+    FlushDraw(FlushDraw),
+    Connection(Connection),
+    Connectivity(ConnectivityRange),
+    Pair(BoardPair),
+    TurnCard(TurnCard),
+    HighCard(RankRange),
+    SecondCard(RankRange),
+    Rank(Rank),
+    PairPosition(PairPosition),
+    Pattern(BoardPattern),
+}
+
+impl Condition {
+    const KEYS: [&'static str; 15] = [
+        "h", "s", "fd", "c", "sh", "oe", "gs", "gap", "pa", "t", "hc", "sc", "r", "pp", "bp",
+    ];
+}
+
+impl FromStr for Condition {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Condition, ParseError> {
+        let (key, value) = s
+            .split_once(':')
+            .ok_or_else(|| ParseError::str("condition", s))?;
+        let upper = &value.to_uppercase()[..];
+
+        match &key.to_lowercase()[..] {
+            "h" => Ok(Condition::Height(BoardHeight::try_from(upper)?)),
+            "s" => Ok(Condition::Suit(BoardSuit::try_from(upper)?)),
+            "fd" => Ok(Condition::FlushDraw(FlushDraw::try_from(upper)?)),
+            "c" => Ok(Condition::Connection(Connection::try_from(upper)?)),
+            // The key names the metric, e.g. sh:10+
+            "sh" | "oe" | "gs" | "gap" => Ok(Condition::Connectivity(ConnectivityRange::from_str(
+                &s.to_uppercase(),
+            )?)),
+            "pa" => Ok(Condition::Pair(BoardPair::from_str(upper)?)),
+            "t" => Ok(Condition::TurnCard(TurnCard::try_from(upper)?)),
+            "hc" => Ok(Condition::HighCard(RankRange::from_str(upper)?)),
+            "sc" => Ok(Condition::SecondCard(RankRange::from_str(upper)?)),
+            "r" => Ok(Condition::Rank(Rank::from_str(upper)?)),
+            "pp" => Ok(Condition::PairPosition(PairPosition::from_str(upper)?)),
+            "bp" => Ok(Condition::Pattern(BoardPattern::from_str(upper)?)),
+            _ => Err(ParseError::str("condition", s)),
+        }
+    }
+}
+
+impl Condition {
+    pub fn matches(&self, board: &Board) -> bool {
+        match self {
+            Condition::Height(height) => board.is_height(height),
+            Condition::Suit(suit) => board.is_suit(suit),
+            Condition::FlushDraw(flush_draw) => board.is_flush_draw(flush_draw),
+            Condition::Connection(connection) => board.is_connection(connection),
+            Condition::Connectivity(range) => board.is_connectivity(range),
+            Condition::Pair(pair) => board.is_pair(pair),
+            Condition::TurnCard(turn_card) => board.is_turn_card(turn_card),
+            Condition::HighCard(range) => board.is_high_card(range),
+            Condition::SecondCard(range) => board.is_second_card(range),
+            Condition::Rank(rank) => board.contains_rank(*rank),
+            Condition::PairPosition(position) => board.is_pair_position(position),
+            Condition::Pattern(pattern) => board.matches_pattern(pattern),
+        }
+    }
+}
+
+/*
+ * Conditions combined with not, and, or and parentheses, binding in that order.
+ * E.g. "(h:2BW or h:3BW) and not s:M or pa:P"
+ */
+#[derive(Debug, PartialEq)]
+pub enum Expression {
+    Condition(Condition),
+    Not(Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+}
+
+impl FromStr for Expression {
+    type Err = ExpressionError;
+
+    fn from_str(s: &str) -> Result<Expression, ExpressionError> {
+        let mut parser = Parser {
+            source: s,
+            tokens: tokenize(s),
+            next: 0,
+        };
+
+        let expression = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(expression),
+            Some(token) => Err(parser.error(
+                token.start,
+                format!("expected 'and', 'or' or the end, found '{}'", token.text),
+            )),
+        }
+    }
+}
+
+impl Expression {
+    pub fn matches(&self, board: &Board) -> bool {
+        match self {
+            Expression::Condition(condition) => condition.matches(board),
+            Expression::Not(expression) => !expression.matches(board),
+            Expression::And(left, right) => left.matches(board) && right.matches(board),
+            Expression::Or(left, right) => left.matches(board) || right.matches(board),
+        }
+    }
+}
+
+// A filter expression that can not be parsed, with the position of the offending token
+#[derive(Debug, PartialEq)]
+pub struct ExpressionError {
+    source: String,
+    position: usize,
+    message: String,
+}
+
+impl Display for ExpressionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let column = self.source[..self.position].chars().count();
+
+        write!(
+            f,
+            "{} in filter expression\n    {}\n    {}^",
+            self.message,
+            self.source,
+            " ".repeat(column)
+        )
+    }
+}
+
+impl From<ExpressionError> for Error {
+    fn from(err: ExpressionError) -> Error {
+        Error::Args(err.to_string())
+    }
+}
+
+struct Token<'a> {
+    text: &'a str,
+    // Byte offset in the expression
+    start: usize,
+}
+
+// Parentheses are tokens of their own, everything else is split at whitespace
+fn tokenize(s: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut word_start: Option<usize> = None;
+
+    for (i, c) in s.char_indices() {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            if let Some(start) = word_start.take() {
+                tokens.push(Token {
+                    text: &s[start..i],
+                    start,
+                });
+            }
+
+            if !c.is_whitespace() {
+                tokens.push(Token {
+                    text: &s[i..i + 1],
+                    start: i,
+                });
+            }
+        } else if word_start.is_none() {
+            word_start = Some(i);
+        }
+    }
+
+    if let Some(start) = word_start {
+        tokens.push(Token {
+            text: &s[start..],
+            start,
+        });
+    }
+
+    tokens
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token<'a>>,
+    next: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse_or(&mut self) -> Result<Expression, ExpressionError> {
+        let mut expression = self.parse_and()?;
+        while self.next_if_keyword("or") {
+            expression = Expression::Or(Box::new(expression), Box::new(self.parse_and()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> Result<Expression, ExpressionError> {
+        let mut expression = self.parse_not()?;
+        while self.next_if_keyword("and") {
+            expression = Expression::And(Box::new(expression), Box::new(self.parse_not()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_not(&mut self) -> Result<Expression, ExpressionError> {
+        if self.next_if_keyword("not") {
+            return Ok(Expression::Not(Box::new(self.parse_not()?)));
+        }
+
+        self.parse_operand()
+    }
+
+    fn parse_operand(&mut self) -> Result<Expression, ExpressionError> {
+        let Some(token) = self.peek() else {
+            return Err(self.error(self.source.len(), "expected a condition".to_string()));
+        };
+        let (text, start) = (token.text, token.start);
+
+        match text {
+            "(" => {
+                self.next += 1;
+                let expression = self.parse_or()?;
+
+                match self.peek() {
+                    Some(token) if token.text == ")" => {
+                        self.next += 1;
+                        Ok(expression)
+                    }
+                    Some(token) => {
+                        Err(self
+                            .error(token.start, format!("expected ')', found '{}'", token.text)))
+                    }
+                    None => Err(self.error(
+                        self.source.len(),
+                        format!(
+                            "expected ')' to close the '(' at {}",
+                            self.source[..start].chars().count() + 1
+                        ),
+                    )),
+                }
+            }
+            _ if is_keyword(text) || text == ")" => {
+                Err(self.error(start, format!("expected a condition, found '{text}'")))
+            }
+            _ => {
+                let condition = Condition::from_str(text).map_err(|_| {
+                    let message = match text.split_once(':') {
+                        Some((key, value))
+                            if Condition::KEYS.contains(&&key.to_lowercase()[..]) =>
+                        {
+                            format!("invalid value '{value}' for {key}:")
+                        }
+                        _ => {
+                            let keys: Vec<String> = Condition::KEYS
+                                .iter()
+                                .map(|key| format!("{key}:"))
+                                .collect();
+                            let (last, rest) = keys.split_last().expect("There are keys");
+
+                            format!(
+                                "unknown condition '{text}', expected {} or {last} \
+                                followed by a value of that option",
+                                rest.join(", ")
+                            )
+                        }
+                    };
+
+                    self.error(start, message)
+                })?;
+                self.next += 1;
+
+                Ok(Expression::Condition(condition))
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.next)
+    }
+
+    fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        let is_next = self
+            .peek()
+            .is_some_and(|token| token.text.eq_ignore_ascii_case(keyword));
+        if is_next {
+            self.next += 1;
+        }
+
+        is_next
+    }
+
+    fn error(&self, position: usize, message: String) -> ExpressionError {
+        ExpressionError {
+            source: self.source.to_string(),
+            position,
+            message,
+        }
+    }
+}
+
+fn is_keyword(text: &str) -> bool {
+    ["and", "or", "not"]
+        .iter()
+        .any(|keyword| text.eq_ignore_ascii_case(keyword))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Expression {
+        Expression::from_str(s).unwrap()
+    }
+
+    fn condition(s: &str) -> Box<Expression> {
+        Box::new(Expression::Condition(Condition::from_str(s).unwrap()))
+    }
+
+    fn board(s: &str) -> Board {
+        Board::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn test_condition_from_str() {
+        assert_eq!(
+            Condition::from_str("h:2bw").unwrap(),
+            Condition::Height(BoardHeight::DoubleBW)
+        );
+        assert_eq!(
+            Condition::from_str("fd:nut:k").unwrap(),
+            Condition::FlushDraw(FlushDraw::Nut(Rank::K))
+        );
+        assert_eq!(
+            Condition::from_str("gap:3-").unwrap(),
+            Condition::Connectivity(ConnectivityRange::from_str("GAP:3-").unwrap())
+        );
+        assert_eq!(
+            Condition::from_str("PA:T").unwrap(),
+            Condition::Pair(BoardPair::Trips)
+        );
+        assert_eq!(
+            Condition::from_str("t:T").unwrap(),
+            Condition::TurnCard(TurnCard::TripsMaking)
+        );
+        assert_eq!(
+            Condition::from_str("hc:k+").unwrap(),
+            Condition::HighCard(RankRange::from_str("K+").unwrap())
+        );
+        assert_eq!(
+            Condition::from_str("pp:low").unwrap(),
+            Condition::PairPosition(PairPosition::Low)
+        );
+        assert_eq!(
+            Condition::from_str("bp:Kxx:ssy").unwrap(),
+            Condition::Pattern(BoardPattern::from_str("Kxx:ssy").unwrap())
+        );
+        assert!(Condition::from_str("2BW").is_err());
+        assert!(Condition::from_str("x:2BW").is_err());
+        assert!(Condition::from_str("h:4BW").is_err());
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(
+            parse("h:2BW or h:3BW and not s:M"),
+            Expression::Or(
+                condition("h:2BW"),
+                Box::new(Expression::And(
+                    condition("h:3BW"),
+                    Box::new(Expression::Not(condition("s:M")))
+                ))
+            )
+        );
+        assert_eq!(
+            parse("(h:2BW OR h:3BW) And s:R"),
+            Expression::And(
+                Box::new(Expression::Or(condition("h:2BW"), condition("h:3BW"))),
+                condition("s:R")
+            )
+        );
+        assert_eq!(parse("not not pa:P"), parse("not(not(pa:P))"));
+    }
+
+    #[test]
+    fn test_matches() {
+        let expression = parse("(h:2BW or h:3BW) and not s:M or pa:P");
+
+        assert!(expression.matches(&board("KsQd2c")));
+        assert!(expression.matches(&board("AsKdQc")));
+        assert!(!expression.matches(&board("KsQs2s")));
+        assert!(!expression.matches(&board("Ks7d2c")));
+        assert!(expression.matches(&board("7s7d2s")));
+
+        let expression = parse("hc:A or r:K and pp:HIGH");
+        assert!(expression.matches(&board("As7d2c")));
+        assert!(expression.matches(&board("KsKd2c")));
+        assert!(!expression.matches(&board("Ks2d2c")));
+
+        let expression = parse("bp:A[KQJ]x and not bp::sss");
+        assert!(expression.matches(&board("AsJd4c")));
+        assert!(!expression.matches(&board("AsJs4s")));
+
+        let expression = parse("s:T and not fd:LOW");
+        assert!(expression.matches(&board("Ks7s4d")));
+        assert!(!expression.matches(&board("Kd7s4s")));
+
+        let expression = parse("sh:1+ or oe:1+ and gap:8-");
+        assert!(expression.matches(&board("Ts9d8c")));
+        assert!(expression.matches(&board("KhTh2c")));
+        assert!(!expression.matches(&board("Kh8h2h")));
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |s: &str| Expression::from_str(s).err().unwrap().to_string();
+
+        assert_eq!(
+            error("h:2BW and (s:M or)"),
+            "expected a condition, found ')' in filter expression\n    \
+            h:2BW and (s:M or)\n                     ^"
+        );
+        assert_eq!(
+            error("h:2BW and x:1"),
+            "unknown condition 'x:1', expected h:, s:, fd:, c:, sh:, oe:, gs:, gap:, pa:, t:, \
+            hc:, sc:, r:, pp: or bp: followed by a value of that option in filter expression\n    \
+            h:2BW and x:1\n              ^"
+        );
+        assert_eq!(
+            error("h:4BW or S:Q"),
+            "invalid value '4BW' for h: in filter expression\n    \
+            h:4BW or S:Q\n    ^"
+        );
+        assert_eq!(
+            error("h:2BW or S:Q"),
+            "invalid value 'Q' for S: in filter expression\n    \
+            h:2BW or S:Q\n             ^"
+        );
+        // Columns count characters, the no-break space takes two bytes
+        assert_eq!(
+            error("h:2BW\u{a0}or (h:3BW"),
+            "expected ')' to close the '(' at 10 in filter expression\n    \
+            h:2BW\u{a0}or (h:3BW\n                   ^"
+        );
+        assert_eq!(
+            error("(h:2BW or h:3BW"),
+            "expected ')' to close the '(' at 1 in filter expression\n    \
+            (h:2BW or h:3BW\n                   ^"
+        );
+        assert_eq!(
+            error("h:2BW s:M"),
+            "expected 'and', 'or' or the end, found 's:M' in filter expression\n    \
+            h:2BW s:M\n          ^"
+        );
+        assert_eq!(
+            error(""),
+            "expected a condition in filter expression\n    \n    ^"
+        );
+    }
+}
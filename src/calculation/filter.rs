@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use crate::{
+    args::Args,
+    poker::board::{texture::Texture, Board},
+};
+
+/*
+ * Decides whether a board matches the texture conditions of the command line.
+ * Every size file of a spot holds the same boards, so each one is classified once
+ * and the result is shared by all sizes.
+ */
+pub struct BoardFilter<'a> {
+    args: &'a Args,
+    classified: HashMap<Board, bool>,
+}
+
+impl<'a> BoardFilter<'a> {
+    pub fn new(args: &'a Args) -> BoardFilter<'a> {
+        BoardFilter {
+            args,
+            classified: HashMap::new(),
+        }
+    }
+
+    pub fn matches(&mut self, board: &Board) -> bool {
+        if let Some(matches) = self.classified.get(board) {
+            return *matches;
+        }
+
+        let matches = board_matches_conditions(board, self.args);
+        self.classified.insert(board.clone(), matches);
+        matches
+    }
+}
+
+fn board_matches_conditions(board: &Board, args: &Args) -> bool {
+    let texture = Texture::of(board);
+
+    let heights_match = any_or_empty(&args.heights, |height| texture.is_height(height));
+    let suits_match = any_or_empty(&args.suits, |suit| texture.is_suit(suit));
+    let flush_draws_match = any_or_empty(&args.flush_draws, |flush_draw| {
+        board.is_flush_draw(flush_draw)
+    });
+    let connections_match = any_or_empty(&args.connections, |connection| {
+        texture.is_connection(connection)
+    });
+    let connectivity_match = any_or_empty(&args.connectivity, |range| board.is_connectivity(range));
+    let pair_match = any_or_empty(&args.pair, |pair| texture.is_pair(pair));
+    let turn_cards_match =
+        any_or_empty(&args.turn_cards, |turn_card| board.is_turn_card(turn_card));
+
+    let high_cards_match = any_or_empty(&args.high_cards, |range| board.is_high_card(range));
+    let second_cards_match = any_or_empty(&args.second_cards, |range| board.is_second_card(range));
+    let ranks_match = any_or_empty(&args.ranks, |rank| board.contains_rank(*rank));
+    let pair_positions_match = any_or_empty(&args.pair_positions, |position| {
+        board.is_pair_position(position)
+    });
+    let patterns_match = any_or_empty(&args.patterns, |pattern| board.matches_pattern(pattern));
+    let filter_match = args
+        .filter
+        .as_ref()
+        .is_none_or(|filter| filter.matches(board));
+
+    heights_match
+        && suits_match
+        && flush_draws_match
+        && connections_match
+        && connectivity_match
+        && pair_match
+        && turn_cards_match
+        && high_cards_match
+        && second_cards_match
+        && ranks_match
+        && pair_positions_match
+        && patterns_match
+        && filter_match
+}
+
+// A condition without values matches every board
+fn any_or_empty<T>(values: &[T], matches: impl Fn(&T) -> bool) -> bool {
+    values.is_empty() || values.iter().any(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr};
+
+    use crate::{
+        calculation::{expression::Expression, weight::Weighting},
+        poker::board::{pair::BoardPair, suit::BoardSuit},
+    };
+
+    use super::*;
+
+    fn args() -> Args {
+        Args {
+            suits: vec![BoardSuit::Twotone],
+            pair: vec![BoardPair::Paired],
+            weighting: Weighting::Uniform,
+            ..Args::for_test(PathBuf::from("./test_data"))
+        }
+    }
+
+    #[test]
+    fn test_board_filter() {
+        let args = args();
+        let mut board_filter = BoardFilter::new(&args);
+
+        let paired = Board::try_from("8s8d6d").unwrap();
+        let unpaired = Board::try_from("Ks7d4d").unwrap();
+
+        assert!(board_filter.matches(&paired));
+        assert!(!board_filter.matches(&unpaired));
+        assert!(board_filter.matches(&paired));
+        assert_eq!(board_filter.classified.len(), 2);
+    }
+
+    #[test]
+    fn test_board_filter_expression() {
+        let args = Args {
+            filter: Some(Expression::from_str("not h:LOW or s:M").unwrap()),
+            ..args()
+        };
+        let mut board_filter = BoardFilter::new(&args);
+
+        assert!(board_filter.matches(&Board::try_from("8s8d6d").unwrap()));
+        assert!(!board_filter.matches(&Board::try_from("5s5d3d").unwrap()));
+        assert!(!board_filter.matches(&Board::try_from("KsKdKc").unwrap()));
+    }
+}
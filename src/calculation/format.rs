@@ -0,0 +1,157 @@
+const BOM: char = '\u{feff}';
+const SEPARATOR: char = '\t';
+const SEPARATOR_CANDIDATES: [char; 3] = [SEPARATOR, ';', ','];
+
+/*
+ * Reports saved through a spreadsheet may use ';' or ',' as separator, decimal commas,
+ * '%' suffixes, quoted cells, a UTF-8 BOM or CRLF line endings. Quoted cells may hold
+ * the separator, e.g. "48,567" or "Bet 18,15" with ',' as separator.
+ * The format is detected from the header, then each line is rewritten on its own
+ * to the tab separated, dot decimal form the parser expects.
+ */
+pub struct ReportFormat {
+    separator: char,
+    // With ',' as separator only a comma inside quotes can be a decimal comma
+    has_decimal_comma: bool,
+}
+
+impl ReportFormat {
+    // The format along with the normalized header
+    pub fn detect(header: &str) -> (ReportFormat, String) {
+        let header = header.strip_prefix(BOM).unwrap_or(header);
+        let separator = detect_separator(header);
+        let format = ReportFormat {
+            separator,
+            has_decimal_comma: separator != ',',
+        };
+        let header = format.normalize_line(header);
+
+        (format, header)
+    }
+
+    pub fn normalize_line(&self, line: &str) -> String {
+        split_cells(line.trim_end_matches(['\r', '\n']), self.separator)
+            .into_iter()
+            .map(|cell| normalize_cell(cell, self.has_decimal_comma))
+            .collect::<Vec<_>>()
+            .join(&SEPARATOR.to_string())
+    }
+}
+
+// Only looks outside of quotes, where every other part of the header is
+fn detect_separator(header: &str) -> char {
+    SEPARATOR_CANDIDATES
+        .into_iter()
+        .find(|candidate| {
+            header
+                .split('"')
+                .step_by(2)
+                .any(|part| part.contains(*candidate))
+        })
+        .unwrap_or(SEPARATOR)
+}
+
+// Separators inside quotes belong to the cell
+fn split_cells(line: &str, separator: char) -> Vec<&str> {
+    let mut cells = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+
+    for (index, c) in line.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if c == separator && !quoted {
+            cells.push(&line[start..index]);
+            start = index + c.len_utf8();
+        }
+    }
+
+    cells.push(&line[start..]);
+    cells
+}
+
+fn normalize_cell(cell: &str, has_decimal_comma: bool) -> String {
+    let cell = cell.trim();
+    let unquoted = cell
+        .strip_prefix('"')
+        .and_then(|cell| cell.strip_suffix('"'));
+    let is_quoted = unquoted.is_some();
+    let cell = unquoted.unwrap_or(cell).trim();
+    let cell = cell.strip_suffix('%').unwrap_or(cell).trim_end();
+
+    if has_decimal_comma || is_quoted {
+        cell.replace(',', ".")
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize_report(content: &str) -> String {
+        let mut lines = content.lines();
+        let (format, header) = ReportFormat::detect(lines.next().unwrap_or_default());
+
+        std::iter::once(header)
+            .chain(lines.map(|line| format.normalize_line(line)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    const EXPECTED: &str = "Tree\tEquity(*)\tEV\tBet 18.15\tCheck
+8s8d8c\t48.567\t36.457\t81.582\t10.43\t
+8s8d6d\t66.065\t10.514\t35.860\t83.144\t";
+
+    #[test]
+    fn test_normalize_report_tab_separated() {
+        assert_eq!(normalize_report(EXPECTED), EXPECTED);
+    }
+
+    #[test]
+    fn test_normalize_report_german_csv() {
+        let content = "\u{feff}Tree;Equity(*);EV;Bet 18,15;Check\r
+8s8d8c;48,567%;36,457;81,582%;10,43%;\r
+8s8d6d;66,065%;10,514;35,860%;83,144%;\r
+";
+
+        assert_eq!(normalize_report(content), EXPECTED);
+    }
+
+    #[test]
+    fn test_normalize_report_quoted_csv() {
+        let content = "\"Tree\",\"Equity(*)\",\"EV\",\"Bet 18.15\",\"Check\"
+\"8s8d8c\",\"48.567\",\"36.457\",\"81.582\",\"10.43\",
+\"8s8d6d\",\"66.065\",\"10.514\",\"35.860\",\"83.144\",";
+
+        assert_eq!(normalize_report(content), EXPECTED);
+    }
+
+    #[test]
+    fn test_normalize_report_quoted_separator() {
+        let content = "Tree,Equity(*),EV,\"Bet 18,15\",Check
+8s8d8c,\"48,567\",36.457,\"81,582%\",10.43,
+\"8s8d6d\",66.065,\"10,514\",35.860,83.144,";
+
+        assert_eq!(normalize_report(content), EXPECTED);
+    }
+
+    #[test]
+    fn test_normalize_report_tab_separated_decimal_comma() {
+        let content = "Tree\tEquity(*)\tEV\tBet 18,15\tCheck\r
+8s8d8c\t48,567 %\t36,457\t81,582 %\t10,43 %\t\r
+8s8d6d\t66,065 %\t10,514\t35,860 %\t83,144 %\t";
+
+        assert_eq!(normalize_report(content), EXPECTED);
+    }
+
+    #[test]
+    fn test_detect_separator() {
+        assert_eq!(detect_separator("Tree\tEV;x,y"), '\t');
+        assert_eq!(detect_separator("Tree;EV;Bet 18,15"), ';');
+        assert_eq!(detect_separator("Tree,EV"), ',');
+        assert_eq!(detect_separator("\"Tree\",\"Bet 18;15\""), ',');
+        assert_eq!(detect_separator("Tree"), '\t');
+    }
+}
@@ -0,0 +1,168 @@
+use std::str::FromStr;
+
+use crate::{
+    error::{Error, Location},
+    poker::ParseError,
+};
+
+use super::schema::SchemaError;
+
+// How rows of a report that can not be parsed are handled
+#[derive(Debug, PartialEq)]
+pub enum Ingestion {
+    Strict,
+    Lenient,
+}
+
+impl FromStr for Ingestion {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Ingestion, ParseError> {
+        match s {
+            "STRICT" => Ok(Ingestion::Strict),
+            "LENIENT" => Ok(Ingestion::Lenient),
+            _ => Err(ParseError::str("ingestion", s)),
+        }
+    }
+}
+
+/*
+ * Collects the rejected rows of one report.
+ * A strict read fails on the first of them, a lenient one skips them all.
+ */
+pub struct RowErrors<'a> {
+    location: &'a Location,
+    ingestion: &'a Ingestion,
+    // In line order, the report is read in a single pass
+    skipped: Vec<(usize, SchemaError)>,
+}
+
+impl<'a> RowErrors<'a> {
+    pub fn new(location: &'a Location, ingestion: &'a Ingestion) -> RowErrors<'a> {
+        RowErrors {
+            location,
+            ingestion,
+            skipped: Vec::new(),
+        }
+    }
+
+    pub fn reject(&mut self, line: usize, text: &str, source: SchemaError) -> Result<(), Error> {
+        match self.ingestion {
+            Ingestion::Strict => Err(Error::Report {
+                location: Box::new(self.location.at_line(line, text)),
+                source,
+            }),
+            Ingestion::Lenient => {
+                self.skipped.push((line, source));
+                Ok(())
+            }
+        }
+    }
+
+    // E.g. "Skipped 3 of 120 rows of <location>: 2 non-numeric cells, 1 duplicate board
+    // (lines 4, 7, 9)"
+    pub fn summary(&self, num_rows: usize) -> Option<String> {
+        if self.skipped.is_empty() {
+            return None;
+        }
+
+        let mut reasons: Vec<(&str, usize)> = Vec::new();
+        for (_, err) in &self.skipped {
+            match reasons
+                .iter_mut()
+                .find(|(reason, _)| *reason == get_reason(err))
+            {
+                Some((_, count)) => *count += 1,
+                None => reasons.push((get_reason(err), 1)),
+            }
+        }
+
+        let reasons: Vec<String> = reasons
+            .into_iter()
+            .map(|(reason, count)| match count {
+                1 => format!("1 {reason}"),
+                _ => format!("{count} {reason}s"),
+            })
+            .collect();
+        let lines: Vec<String> = self
+            .skipped
+            .iter()
+            .map(|(line, _)| line.to_string())
+            .collect();
+
+        Some(format!(
+            "Skipped {} of {num_rows} rows of {}: {} (lines {})",
+            self.skipped.len(),
+            self.location,
+            reasons.join(", "),
+            lines.join(", ")
+        ))
+    }
+}
+
+fn get_reason(err: &SchemaError) -> &'static str {
+    match err {
+        SchemaError::MissingColumn(_) | SchemaError::MissingCell(_) => "missing column",
+        SchemaError::InvalidCell { .. } => "non-numeric cell",
+        SchemaError::InvalidBoard(_) => "unparsable board",
+        SchemaError::DuplicateBoard { .. } => "duplicate board",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn location() -> Location {
+        Location::new(Path::new("./data/BTN vs BB/33/after_check.txt"))
+    }
+
+    fn invalid_cell() -> SchemaError {
+        SchemaError::InvalidCell {
+            column: String::from("EV"),
+            value: String::from("-"),
+        }
+    }
+
+    #[test]
+    fn test_ingestion_from_str() {
+        assert_eq!(Ingestion::from_str("STRICT").unwrap(), Ingestion::Strict);
+        assert_eq!(Ingestion::from_str("LENIENT").unwrap(), Ingestion::Lenient);
+        assert!(Ingestion::from_str("lenient").is_err());
+    }
+
+    #[test]
+    fn test_reject_strict() {
+        let location = location();
+        let mut errors = RowErrors::new(&location, &Ingestion::Strict);
+
+        let err = errors.reject(4, "8s8d8c\t-", invalid_cell()).err().unwrap();
+
+        assert!(err.to_string().contains(", line 4: "));
+        assert_eq!(errors.summary(10), None);
+    }
+
+    #[test]
+    fn test_reject_lenient() {
+        let location = location();
+        let mut errors = RowErrors::new(&location, &Ingestion::Lenient);
+
+        errors.reject(4, "8s8d8c\t-", invalid_cell()).unwrap();
+        errors
+            .reject(
+                7,
+                "8s8d\t50",
+                SchemaError::InvalidBoard(String::from("8s8d")),
+            )
+            .unwrap();
+        errors.reject(9, "Ks7d4c\t-", invalid_cell()).unwrap();
+
+        assert_eq!(
+            errors.summary(10).unwrap(),
+            "Skipped 3 of 10 rows of ./data/BTN vs BB/33/after_check.txt (spot BTN vs BB, size 33): \
+            2 non-numeric cells, 1 unparsable board (lines 4, 7, 9)"
+        );
+    }
+}
@@ -1,166 +1,569 @@
+pub mod boardset;
 pub mod datarow;
+pub mod expression;
+pub mod filter;
+pub mod format;
+pub mod ingestion;
+pub mod schema;
+pub mod sizing;
+pub mod weight;
 
 use std::{
-    collections::HashSet,
-    fs::{self, DirEntry},
-    path::Path,
+    collections::HashMap,
+    fs::{DirEntry, File},
+    io::{BufRead, BufReader},
+    panic,
+    path::{Path, PathBuf},
+    str::FromStr,
+    thread,
 };
 
+use boardset::BoardSetPolicy;
 use datarow::DataRow;
+use filter::BoardFilter;
+use format::ReportFormat;
+use ingestion::{Ingestion, RowErrors};
+use schema::{ColumnSchema, SchemaError};
+use sizing::BetSizing;
+use weight::Weighting;
 
 use crate::{
     args::Args,
+    cache, catalog,
+    error::{Error, Location},
     files,
     poker::{action::Action, betsize::Betsize, board::Board, position::Positions},
 };
 
-const DATA_DIR: &str = "./data";
+pub struct Aggregation {
+    pub data_rows: Vec<DataRow>,
+    pub boards: Vec<Board>,
+    pub pot: Option<f32>,
+    pub sizings: Vec<BetSizing>,
+    pub actions: Vec<Action>,
+    pub warnings: Vec<String>,
+}
+
+// Rows of a size file matching the filters, with their weights and boards
+struct SizeRows {
+    schema: ColumnSchema,
+    rows: Vec<(DataRow, f32)>,
+    boards: Vec<Board>,
+}
+
+pub fn build_aggregation(args: Args) -> Result<Aggregation, Error> {
+    let size_dirs = get_size_dirs(&args.positions, &args.data_dir)?;
 
-pub fn build_data_rows_with_boards(args: Args) -> (Vec<DataRow>, Vec<Board>) {
-    let size_dirs = get_size_dirs(&args.positions, DATA_DIR);
+    let betsizes = if args.betsizes.is_empty() {
+        get_available_betsizes(&size_dirs)
+    } else {
+        args.betsizes.clone()
+    };
 
-    let (datarows, considered_boards) = args
-        .betsizes
+    let paths: Vec<PathBuf> = betsizes
         .iter()
-        .map(|betsize| build_data_row_with_boards(betsize, &size_dirs, &args))
-        .unzip();
+        .map(|betsize| {
+            let size_dir = find_size_dir(betsize, &size_dirs)?;
+            Ok(get_action_file_in_dir(size_dir, &args.actions)?.path())
+        })
+        .collect::<Result<_, Error>>()?;
+
+    let mut warnings = Vec::new();
+    let mut board_filter = BoardFilter::new(&args);
+
+    let loaded_reports = load_reports(&paths, &args.ingestion, args.cache_dir.as_deref())?;
+    let cache_warning = get_cache_warning(&loaded_reports);
+    let mut size_rows: Vec<SizeRows> = loaded_reports
+        .into_iter()
+        .map(|loaded| {
+            warnings.extend(loaded.skipped);
+            select_rows(loaded.report, &mut board_filter, &args.weighting)
+        })
+        .collect();
+    warnings.extend(cache_warning);
+
+    let boards = reconcile_board_sets(&betsizes, &mut size_rows, &args, &mut warnings)?;
+
+    let faces_bet = Action::faces_bet(&args.actions);
+    let mut bet_chips = Vec::new();
+    let mut data_rows = Vec::new();
+
+    for (betsize, size_rows) in betsizes.into_iter().zip(size_rows) {
+        match size_rows.schema.bet_chips() {
+            Some(chips) => bet_chips.push((betsize.clone(), chips)),
+            None if faces_bet => {}
+            None => warnings.push(format!("No bet column found in header of size {betsize}")),
+        }
 
-    (datarows, validate_identical_and_get(considered_boards))
+        let mut data_row = build_data_row(size_rows.rows, &betsize)?;
+        data_row.size = Some(betsize);
+        data_rows.push(data_row);
+    }
+
+    let pot = match args.pot {
+        Some(pot) => Some(pot),
+        None => {
+            let pot = sizing::derive_pot(&get_spot_bet_chips(&size_dirs, &args.actions));
+            if pot.is_none() && !faces_bet {
+                warnings.push(format!(
+                    "The pot could not be verified with fewer than {} bet sizes in the spot, \
+                    pass it with -pot to check the size directories",
+                    sizing::MIN_POT_ESTIMATES
+                ));
+            }
+            pot
+        }
+    };
+
+    // Reports hold IP's equity and EV, so at the root they are turned to OOP's view
+    if args.actions.is_empty() {
+        match pot {
+            Some(pot) => data_rows
+                .iter_mut()
+                .for_each(|row: &mut DataRow| row.switch_to_oop(pot)),
+            None => warnings.push(String::from(
+                "Could not derive the pot, equity and EV are shown for IP",
+            )),
+        }
+    }
+
+    let sizings = sizing::build_bet_sizings(bet_chips, pot);
+    warnings.extend(sizing::validate_bet_sizings(&sizings));
+
+    Ok(Aggregation {
+        data_rows,
+        boards,
+        pot,
+        sizings,
+        actions: args.actions,
+        warnings,
+    })
 }
 
-fn get_size_dirs(positions: &Positions, data_dir: &str) -> Vec<DirEntry> {
-    let pos_dir = get_pos_dir(data_dir, positions);
+pub fn get_size_dirs(positions: &Positions, data_dir: &Path) -> Result<Vec<DirEntry>, Error> {
+    let pos_dir = find_pos_dir(data_dir, positions)?;
     files::get_dirs(&pos_dir.path())
 }
 
-fn get_pos_dir(data_dir: &str, pos: &Positions) -> DirEntry {
-    files::get_dirs(Path::new(data_dir))
+fn get_available_betsizes(size_dirs: &[DirEntry]) -> Vec<Betsize> {
+    let mut betsizes: Vec<Betsize> = size_dirs
+        .iter()
+        .filter_map(|dir| Betsize::from_str(&files::get_name(&dir.path())).ok())
+        .collect();
+
+    betsizes.sort();
+    betsizes.dedup();
+    betsizes
+}
+
+/*
+ * The bet of every size directory of the spot with a file for the actions, not only of
+ * the selected sizes, so the pot does not just echo the labels being checked.
+ * Files that can not be read are left to the sizes that select them.
+ */
+fn get_spot_bet_chips(size_dirs: &[DirEntry], actions: &[Action]) -> Vec<(Betsize, f32)> {
+    size_dirs
+        .iter()
+        .filter_map(|size_dir| {
+            let betsize = Betsize::from_str(&files::get_name(&size_dir.path())).ok()?;
+            let file = get_action_file_in_dir(size_dir, actions).ok()?;
+            let chips = read_schema(&file.path()).ok()?.bet_chips()?;
+            Some((betsize, chips))
+        })
+        .collect()
+}
+
+fn find_pos_dir(data_dir: &Path, pos: &Positions) -> Result<DirEntry, Error> {
+    let pos_dirs = files::get_dirs(data_dir)?;
+
+    let mut dir_names: Vec<String> = pos_dirs
+        .iter()
+        .map(|entry| files::get_name(&entry.path()))
+        .collect();
+    dir_names.sort();
+
+    let mut matching_dirs: Vec<DirEntry> = pos_dirs
         .into_iter()
-        .find(|entry| {
-            let name = files::get_name(&entry.path());
-            name.contains(&pos.ip.to_string()) && name.contains(&pos.oop.to_string())
+        .filter(|entry| {
+            Positions::from_str(&files::get_name(&entry.path()))
+                .is_ok_and(|dir_pos| dir_pos == *pos)
         })
-        .expect("Could not find position directory")
+        .collect();
+
+    match matching_dirs.len() {
+        0 => Err(Error::Data(format!(
+            "No spot directory found for {pos} (IP {}, OOP {}). Candidates: {}",
+            pos.ip,
+            pos.oop,
+            dir_names.join(", ")
+        ))),
+        1 => Ok(matching_dirs.remove(0)),
+        _ => {
+            let mut candidates: Vec<String> = matching_dirs
+                .iter()
+                .map(|entry| files::get_name(&entry.path()))
+                .collect();
+            candidates.sort();
+
+            Err(Error::Data(format!(
+                "Ambiguous spot directories for {pos}. Candidates: {}",
+                candidates.join(", ")
+            )))
+        }
+    }
 }
 
-fn build_data_row_with_boards(
-    betsize: &Betsize,
-    size_dirs: &[DirEntry],
+// Rows of the report whose boards match the filter, with the weight of each
+fn select_rows(
+    report: ParsedReport,
+    board_filter: &mut BoardFilter,
+    weighting: &Weighting,
+) -> SizeRows {
+    let (rows, boards) = report
+        .rows
+        .into_iter()
+        .filter(|row| board_filter.matches(&row.board))
+        .map(|row| {
+            let weight = weight::get_weight(row.weight, &row.board, weighting);
+            ((row.data_row, weight), row.board)
+        })
+        .unzip();
+
+    SizeRows {
+        schema: report.schema,
+        rows,
+        boards,
+    }
+}
+
+/*
+ * Compares the boards of all size files and applies the board set policy if they differ.
+ * Returns every considered board once.
+ */
+fn reconcile_board_sets(
+    betsizes: &[Betsize],
+    size_rows: &mut [SizeRows],
     args: &Args,
-) -> (DataRow, Vec<Board>) {
-    let size_dir = find_size_dir(betsize, size_dirs);
-    let action_file = get_action_file_in_dir(size_dir, &args.actions);
-    let file_content = fs::read_to_string(action_file.path()).expect("Could not read file content");
+    warnings: &mut Vec<String>,
+) -> Result<Vec<Board>, Error> {
+    let board_sets = get_board_sets(betsizes, size_rows);
+
+    let mismatches = boardset::find_mismatches(&board_sets);
+    if mismatches.is_empty() {
+        return Ok(boardset::get_all_boards(&board_sets));
+    }
 
-    let lines_with_boards = get_lines_with_boards(&file_content);
-    let (filteres_lines, filtered_boards) = filter(lines_with_boards, args);
+    match args.board_set_policy {
+        BoardSetPolicy::Abort => Err(Error::Aggregation(format!(
+            "Size files hold different boards, use -m SHARED or -m OWN to aggregate anyway:\n{}",
+            mismatches.join("\n")
+        ))),
+        BoardSetPolicy::Shared => {
+            let shared = boardset::get_shared(&board_sets);
+            warnings.push(String::from(
+                "Only boards of every size file are aggregated",
+            ));
+            warnings.extend(mismatches);
 
-    let mut data_row = build_data_row(&filteres_lines);
-    data_row.size = Some(betsize.clone());
-    (data_row, filtered_boards)
+            for size_rows in size_rows.iter_mut() {
+                let (rows, boards) = size_rows
+                    .rows
+                    .drain(..)
+                    .zip(size_rows.boards.drain(..))
+                    .filter(|(_, board)| shared.contains(&board.canonical()))
+                    .unzip();
+
+                size_rows.rows = rows;
+                size_rows.boards = boards;
+            }
+
+            Ok(boardset::get_all_boards(&get_board_sets(
+                betsizes, size_rows,
+            )))
+        }
+        BoardSetPolicy::Own => {
+            let boards = boardset::get_all_boards(&board_sets);
+            warnings.push(String::from("Each size is aggregated over its own boards"));
+            warnings.extend(mismatches);
+            Ok(boards)
+        }
+    }
 }
 
-fn find_size_dir<'a>(betsize: &Betsize, size_dirs: &'a [DirEntry]) -> &'a DirEntry {
-    size_dirs
+fn get_board_sets<'a>(
+    betsizes: &'a [Betsize],
+    size_rows: &'a [SizeRows],
+) -> Vec<(&'a Betsize, &'a [Board])> {
+    betsizes
         .iter()
-        .find(|dir| files::get_name(&dir.path()) == betsize.to_string())
-        .unwrap_or_else(|| panic!("Could not find size dir for {betsize}"))
+        .zip(size_rows)
+        .map(|(betsize, size_rows)| (betsize, &size_rows.boards[..]))
+        .collect()
 }
 
-fn get_action_file_in_dir(dir: &DirEntry, actions: &[Action]) -> DirEntry {
-    files::get_files(&dir.path())
-        .into_iter()
-        .find(|file| file_matches_actions(file, actions))
-        .expect("Could not find action file")
+// A report with its header read, the rows follow from the reader line by line
+struct Report<R: BufRead> {
+    location: Location,
+    schema: ColumnSchema,
+    format: ReportFormat,
+    reader: R,
 }
 
-fn file_matches_actions(file: &DirEntry, actions: &[Action]) -> bool {
-    let filename = files::get_name(&file.path());
-    let split_pattern = '_';
-
-    filename.matches(split_pattern).count() == actions.len()
-        && filename
-            .split(split_pattern)
-            .skip(1)
-            .zip(actions.iter())
-            .all(|(action_name, action)| action_name == action.to_long_string())
+// A report with every row parsed, as read from its file or from the cache
+pub struct ParsedReport {
+    pub schema: ColumnSchema,
+    // Rows of the file, including the ones a lenient read skipped
+    pub num_rows: usize,
+    pub rows: Vec<ParsedRow>,
 }
 
-fn get_lines_with_boards(file_content: &str) -> Vec<(&str, Board)> {
-    let lines_with_boards = file_content
-        .lines()
-        .skip(1)
-        .filter(|line| !line.is_empty())
-        .map(|line| (line, extract_board(line)))
-        .collect();
+pub struct ParsedRow {
+    pub board: Board,
+    pub data_row: DataRow,
+    // Value of the weight column, if the export has one
+    pub weight: Option<f32>,
+}
+
+pub struct LoadedReport {
+    pub report: ParsedReport,
+    // Summary of the rows a lenient read skipped, such a report is not cached
+    pub skipped: Option<String>,
+    // Why the parsed report could not be written to the cache
+    pub cache_error: Option<Error>,
+}
+
+// Loads the reports on one thread each, in the order of their paths
+pub fn load_reports(
+    paths: &[PathBuf],
+    ingestion: &Ingestion,
+    cache_dir: Option<&Path>,
+) -> Result<Vec<LoadedReport>, Error> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .iter()
+            .map(|path| scope.spawn(move || load_report(path, ingestion, cache_dir)))
+            .collect();
 
-    validate_no_duplicates(&lines_with_boards);
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err))
+            })
+            .collect()
+    })
+}
+
+/*
+ * Reads and parses the report, unless the cache holds it for the file's current size and
+ * modification time. Only reports without rejected rows are cached, so a lenient read of
+ * a corrupt file parses it again and still reports the rows it skips.
+ */
+pub fn load_report(
+    path: &Path,
+    ingestion: &Ingestion,
+    cache_dir: Option<&Path>,
+) -> Result<LoadedReport, Error> {
+    if let Some(report) = cache_dir.and_then(|cache_dir| cache::load(cache_dir, path)) {
+        return Ok(LoadedReport {
+            report,
+            skipped: None,
+            cache_error: None,
+        });
+    }
+
+    let (report, skipped) = parse_report(read_report(path)?, ingestion)?;
+
+    let cache_error = match (cache_dir, &skipped) {
+        (Some(cache_dir), None) => cache::store(cache_dir, path, &report).err(),
+        _ => None,
+    };
+
+    Ok(LoadedReport {
+        report,
+        skipped,
+        cache_error,
+    })
+}
+
+// Reports of one run share the cache directory, so its first write error stands for all
+pub fn get_cache_warning(loaded_reports: &[LoadedReport]) -> Option<String> {
+    let err = loaded_reports
+        .iter()
+        .find_map(|loaded| loaded.cache_error.as_ref())?;
 
-    lines_with_boards
+    Some(format!(
+        "Could not write the report cache, the next run parses the reports again: {err}"
+    ))
 }
 
-fn extract_board(line: &str) -> Board {
-    let board_str = line
-        .split('\t')
-        .next()
-        .expect("Error getting board from line");
+fn read_report(path: &Path) -> Result<Report<BufReader<File>>, Error> {
+    let location = Location::new(path);
+    let mut reader = files::open_file(path)?;
 
-    Board::try_from(board_str).unwrap()
+    let mut header = String::new();
+    reader.read_line(&mut header).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let (format, header) = ReportFormat::detect(&header);
+
+    let schema = ColumnSchema::parse(&header).map_err(|source| Error::Report {
+        location: Box::new(location.at_line(1, &header)),
+        source,
+    })?;
+
+    Ok(Report {
+        location,
+        schema,
+        format,
+        reader,
+    })
+}
+
+// The schema from the header line alone, without reading the rows
+fn read_schema(path: &Path) -> Result<ColumnSchema, Error> {
+    Ok(read_report(path)?.schema)
 }
 
-fn validate_no_duplicates(lines_with_boards: &Vec<(&str, Board)>) {
-    let boards: HashSet<&Board> = lines_with_boards.iter().map(|(_, board)| board).collect();
-    assert_eq!(boards.len(), lines_with_boards.len());
+fn find_size_dir<'a>(betsize: &Betsize, size_dirs: &'a [DirEntry]) -> Result<&'a DirEntry, Error> {
+    size_dirs
+        .iter()
+        .find(|dir| {
+            Betsize::from_str(&files::get_name(&dir.path())).is_ok_and(|size| size == *betsize)
+        })
+        .ok_or_else(|| Error::Data(format!("Could not find size dir for {betsize}")))
 }
 
-fn filter(lines_with_boards: Vec<(&str, Board)>, args: &Args) -> (Vec<String>, Vec<Board>) {
-    lines_with_boards
+pub fn get_action_file_in_dir(dir: &DirEntry, actions: &[Action]) -> Result<DirEntry, Error> {
+    files::get_files(&dir.path())?
         .into_iter()
-        .filter_map(|(line, board)| {
-            board_matches_conditions(&board, args).then_some((line.to_string(), board))
+        .find(|file| file_matches_actions(file, actions))
+        .ok_or_else(|| {
+            Error::Data(format!(
+                "Could not find action file for {} in {}",
+                Action::format_path(actions),
+                dir.path().display()
+            ))
         })
-        .unzip()
 }
 
-fn board_matches_conditions(board: &Board, args: &Args) -> bool {
-    let heights_match =
-        args.heights.is_empty() || args.heights.iter().any(|height| board.is_height(height));
-    let suits_match = args.suits.is_empty() || args.suits.iter().any(|suit| board.is_suit(suit));
-    let connections_match = args.connections.is_empty()
-        || args
-            .connections
-            .iter()
-            .any(|connection| board.is_connection(connection));
-    let pair_match = args.pair.is_empty() || args.pair.iter().any(|pair| board.is_pair(pair));
+fn file_matches_actions(file: &DirEntry, actions: &[Action]) -> bool {
+    catalog::parse_action_file_name(&files::get_name(&file.path()))
+        .is_some_and(|file_actions| file_actions == actions)
+}
+
+/*
+ * Parses the rows as they are read, each line is normalized on its own.
+ * Returns the summary of the rows a lenient read skipped along with them.
+ * The header is line 1, so body lines start at 2.
+ */
+fn parse_report(
+    report: Report<impl BufRead>,
+    ingestion: &Ingestion,
+) -> Result<(ParsedReport, Option<String>), Error> {
+    let Report {
+        location,
+        schema,
+        format,
+        reader,
+    } = report;
+    let mut row_errors = RowErrors::new(&location, ingestion);
+    let mut num_rows = 0;
+    let mut rows = Vec::new();
+    // Isomorphic boards are the same spot under another name, so they count as duplicates too
+    let mut canonical_boards: HashMap<Board, Board> = HashMap::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|source| Error::Io {
+            path: location.path.clone(),
+            source,
+        })?;
+        let text = format.normalize_line(&line);
+        if text.is_empty() {
+            continue;
+        }
+        num_rows += 1;
+
+        match parse_row(&text, &schema, &canonical_boards) {
+            Ok((row, canonical)) => {
+                canonical_boards.insert(canonical, row.board.clone());
+                rows.push(row);
+            }
+            Err(err) => row_errors.reject(index + 2, &text, err)?,
+        }
+    }
 
-    heights_match && suits_match && connections_match && pair_match
+    let skipped = row_errors.summary(num_rows);
+
+    Ok((
+        ParsedReport {
+            schema,
+            num_rows,
+            rows,
+        },
+        skipped,
+    ))
 }
 
-fn build_data_row(lines: &[String]) -> DataRow {
-    let data_rows: Vec<DataRow> = lines.iter().map(|line| DataRow::new(line)).collect();
-    let count = data_rows.len();
-    assert!(count > 0, "No lines found matching conditions");
+// The row along with the canonical form of its board, splitting the line only once
+fn parse_row(
+    text: &str,
+    schema: &ColumnSchema,
+    canonical_boards: &HashMap<Board, Board>,
+) -> Result<(ParsedRow, Board), SchemaError> {
+    let cells: Vec<&str> = text.split('\t').collect();
+    let board_str = schema.cell(&cells, schema.tree)?;
+    let board =
+        Board::try_from(board_str).map_err(|_| SchemaError::InvalidBoard(board_str.to_string()))?;
+    let canonical = board.canonical();
 
-    data_rows
-        .into_iter()
-        .reduce(|row1, row2| row1 + row2)
-        .map(|sum_row| sum_row / count)
-        .expect("Could not calculate data row")
+    if let Some(other) = canonical_boards.get(&canonical) {
+        return Err(SchemaError::DuplicateBoard {
+            board: board.to_string(),
+            other: other.to_string(),
+        });
+    }
+
+    let row = ParsedRow {
+        data_row: DataRow::new(&cells, schema)?,
+        weight: weight::read_weight_column(&cells, schema)?,
+        board,
+    };
+
+    Ok((row, canonical))
 }
 
-fn validate_identical_and_get(boards: Vec<Vec<Board>>) -> Vec<Board> {
-    boards
-        .into_iter()
-        .reduce(|acc, board| {
-            assert_eq!(acc, board);
-            acc
-        })
-        .expect("Could not reduce considered boards")
+fn build_data_row(rows: Vec<(DataRow, f32)>, betsize: &Betsize) -> Result<DataRow, Error> {
+    if rows.is_empty() {
+        return Err(Error::Aggregation(format!(
+            "No lines of size {betsize} match the conditions"
+        )));
+    }
+
+    let total_weight: f32 = rows.iter().map(|(_, weight)| weight).sum();
+    if total_weight <= 0.0 {
+        return Err(Error::Aggregation(format!(
+            "Weights of the matching lines of size {betsize} sum to 0"
+        )));
+    }
+
+    rows.into_iter()
+        .map(|(row, weight)| row * weight)
+        .reduce(|row1, row2| row1 + row2)
+        .map(|sum_row| sum_row / total_weight)
+        .ok_or_else(|| Error::Aggregation(String::from("Could not calculate data row")))
 }
 
 #[cfg(test)]
 mod tests {
-    use files::get_name;
+    use std::{fs, path::PathBuf};
+
+    use datarow::bet_check_freqs;
+    use files::{get_name, TestDir};
+
+    use weight::Weighting;
 
     use crate::poker::{
         board::{height::BoardHeight, pair::BoardPair, suit::BoardSuit},
@@ -171,6 +574,10 @@ mod tests {
 
     const DATA_DIR: &str = "./test_data";
 
+    fn data_dir() -> PathBuf {
+        PathBuf::from(DATA_DIR)
+    }
+
     #[test]
     fn test_get_size_dirs_names() {
         let positions = Positions {
@@ -178,7 +585,8 @@ mod tests {
             oop: Position::BB,
         };
 
-        let mut size_dirs_names: Vec<_> = get_size_dirs(&positions, DATA_DIR)
+        let mut size_dirs_names: Vec<_> = get_size_dirs(&positions, Path::new(DATA_DIR))
+            .unwrap()
             .into_iter()
             .map(|dir| get_name(&dir.path()))
             .collect();
@@ -197,64 +605,462 @@ mod tests {
     }
 
     #[test]
-    fn test_build_data_rows_with_boards_1() {
+    fn test_build_aggregation_sizings() {
         let args = Args {
-            positions: Positions {
-                ip: Position::Btn,
-                oop: Position::BB,
-            },
-            pair: Vec::new(),
-            suits: Vec::new(),
-            heights: Vec::new(),
-            actions: vec![Action::Check],
-            betsizes: vec![
-                Betsize::Size33,
-                Betsize::Size50,
-                Betsize::Size75,
-                Betsize::Size150,
-            ],
-            connections: Vec::new(),
+            weighting: Weighting::Uniform,
+            ..Args::for_test(data_dir())
         };
 
-        let size_dirs = get_size_dirs(&args.positions, DATA_DIR);
+        let aggregation = build_aggregation(args).unwrap();
+
+        assert_eq!(aggregation.pot, Some(55.0));
+        assert!(aggregation.warnings.is_empty());
 
-        let (datarows, considered_boards): (Vec<DataRow>, Vec<Vec<Board>>) = args
-            .betsizes
+        let betsizes: Vec<_> = aggregation
+            .sizings
             .iter()
-            .map(|betsize| build_data_row_with_boards(betsize, &size_dirs, &args))
-            .unzip();
+            .map(|sizing| &sizing.betsize)
+            .collect();
+        assert_eq!(
+            betsizes,
+            vec![
+                &Betsize::Percent(33),
+                &Betsize::Percent(50),
+                &Betsize::Percent(75),
+                &Betsize::Percent(150),
+            ]
+        );
+        assert!(aggregation.sizings.iter().all(|sizing| {
+            let label = sizing.betsize.percent().unwrap() as f32;
+            (sizing.percent.unwrap() - label).abs() < 0.01
+        }));
+    }
+
+    #[test]
+    fn test_build_aggregation_cache_not_writable() {
+        // A file where the cache directory should be fails every write of the run
+        let test_dir = TestDir::new("unwritable-cache");
+        let cache_dir = test_dir.path().join("cache");
+        fs::write(&cache_dir, "").unwrap();
+        let args = Args {
+            weighting: Weighting::Uniform,
+            cache_dir: Some(cache_dir),
+            ..Args::for_test(data_dir())
+        };
+
+        let aggregation = build_aggregation(args).unwrap();
+
+        assert_eq!(aggregation.warnings.len(), 1);
+        assert!(aggregation.warnings[0].starts_with("Could not write the report cache"));
+    }
+
+    #[test]
+    fn test_build_aggregation_pot_from_every_size() {
+        let args = Args {
+            betsizes: vec![Betsize::Percent(75)],
+            ..Args::for_test(data_dir())
+        };
+
+        let aggregation = build_aggregation(args).unwrap();
+
+        assert_eq!(aggregation.pot, Some(55.0));
+        assert!(aggregation.warnings.is_empty());
+    }
+
+    fn mislabeled_args(data_dir: &Path) -> Args {
+        let header = "Tree\tEquity(*)\tEV\tBet 18.15\tCheck\n";
+        // A bet of 150% in the directory for 75%
+        let mislabeled_header = "Tree\tEquity(*)\tEV\tBet 82.5\tCheck\n";
+
+        fs::create_dir_all(data_dir.join("BTN vs BB/33")).unwrap();
+        fs::create_dir_all(data_dir.join("BTN vs BB/75")).unwrap();
+        fs::write(
+            data_dir.join("BTN vs BB/33/after_check.txt"),
+            format!("{header}Ks7d4c\t50\t30\t20\t80\n"),
+        )
+        .unwrap();
+        fs::write(
+            data_dir.join("BTN vs BB/75/after_check.txt"),
+            format!("{mislabeled_header}Ks7d4c\t50\t20\t10\t90\n"),
+        )
+        .unwrap();
+
+        Args::for_test(data_dir)
+    }
+
+    #[test]
+    fn test_build_aggregation_mislabeled_of_two_sizes() {
+        let test_dir = TestDir::new("mislabeled-size");
+
+        let aggregation = build_aggregation(mislabeled_args(test_dir.path())).unwrap();
+
+        assert_eq!(aggregation.pot, None);
+        assert_eq!(
+            aggregation.warnings,
+            vec![
+                "The pot could not be verified with fewer than 3 bet sizes in the spot, \
+                pass it with -pot to check the size directories"
+            ]
+        );
+
+        let args = Args {
+            pot: Some(55.0),
+            ..mislabeled_args(test_dir.path())
+        };
+        let aggregation = build_aggregation(args).unwrap();
+
+        assert_eq!(
+            aggregation.warnings,
+            vec!["Size directory 75 contains a bet of 82.5 chips, which is 150.0% of the pot"]
+        );
+    }
+
+    #[test]
+    fn test_build_aggregation_root() {
+        let args = Args {
+            positions: Positions::new(Position::CO, Position::BB).unwrap(),
+            pair: vec![BoardPair::Unpaired],
+            actions: Vec::new(),
+            betsizes: vec![Betsize::Percent(33), Betsize::Percent(50)],
+            // Only two sizes of the spot have a root file
+            pot: Some(55.0),
+            weighting: Weighting::Uniform,
+            ..Args::for_test(data_dir())
+        };
+
+        let aggregation = build_aggregation(args).unwrap();
+
+        assert_eq!(aggregation.pot, Some(55.0));
+        assert!(aggregation.warnings.is_empty());
+        assert_eq!(aggregation.boards, vec![Board::try_from("Ks7d4c").unwrap()]);
+        assert_eq!(
+            aggregation.data_rows,
+            vec![
+                DataRow {
+                    size: Some(Betsize::Percent(33)),
+                    eq: 100.0 - 58.4,
+                    ev: 55.0 - 33.1,
+                    freqs: bet_check_freqs(18.15, 0.0, 100.0),
+                },
+                DataRow {
+                    size: Some(Betsize::Percent(50)),
+                    eq: 100.0 - 58.4,
+                    ev: 55.0 - 33.0,
+                    freqs: bet_check_freqs(27.5, 3.0, 97.0),
+                },
+            ]
+        );
+    }
+
+    fn report(body: &str) -> Report<&[u8]> {
+        let header = "Tree\tEquity(*)\tEV\tBet 18.15\tCheck";
+
+        Report {
+            location: Location::new(Path::new("./data/BTN vs BB/33/after_check.txt")),
+            schema: ColumnSchema::parse(header).unwrap(),
+            format: ReportFormat::detect(header).0,
+            reader: body.as_bytes(),
+        }
+    }
+
+    fn parse(report: Report<&[u8]>, ingestion: &Ingestion) -> Result<ParsedReport, Error> {
+        parse_report(report, ingestion).map(|(parsed_report, _)| parsed_report)
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let report = report("KsQs2d\t50\t30\t20\t80\n\n8s8d8c\t50\t30\t20\t80");
+        let parsed_report = parse(report, &Ingestion::Strict).unwrap();
+
+        assert_eq!(parsed_report.num_rows, 2);
+        let boards: Vec<_> = parsed_report.rows.iter().map(|row| &row.board).collect();
+        assert_eq!(
+            boards,
+            vec![
+                &Board::try_from("KsQs2d").unwrap(),
+                &Board::try_from("8s8d8c").unwrap()
+            ]
+        );
+        assert_eq!(parsed_report.rows[1].data_row.ev, 30.0);
+        assert_eq!(parsed_report.rows[1].weight, None);
+    }
+
+    #[test]
+    fn test_parse_report_lenient_skipped_board_is_no_duplicate() {
+        let report = report("KsQs2d\t50\t-\t20\t80\nKhQh2c\t50\t30\t20\t80");
+        let parsed_report = parse(report, &Ingestion::Lenient).unwrap();
+
+        assert_eq!(parsed_report.num_rows, 2);
+        assert_eq!(parsed_report.rows.len(), 1);
+        assert_eq!(
+            parsed_report.rows[0].board,
+            Board::try_from("KhQh2c").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_report_duplicates_isomorphic() {
+        let report =
+            report("KsQs2d\t50\t30\t20\t80\nKsQd2s\t50\t30\t20\t80\nKhQh2c\t50\t30\t20\t80");
+        let err = parse(report, &Ingestion::Strict).err().unwrap();
+
+        assert_eq!(
+            err.to_string(),
+            "./data/BTN vs BB/33/after_check.txt (spot BTN vs BB, size 33), line 4: \
+            boards KsQs2d and KhQh2c are duplicates up to suit isomorphism\n    \
+            KhQh2c  50  30  20  80"
+        );
+    }
+
+    #[test]
+    fn test_parse_report_invalid_board() {
+        let report = report("KsQs2d\t50\t30\t20\t80\nKsQs\t50\t30\t20\t80");
+        let err = parse(report, &Ingestion::Strict).err().unwrap();
+
+        let Error::Report { location, source } = err else {
+            panic!("expected a report error, got {err}");
+        };
+        assert_eq!(location.line, Some(3));
+        assert_eq!(source, SchemaError::InvalidBoard(String::from("KsQs")));
+    }
+
+    fn corrupt_args(data_dir: &Path, ingestion: Ingestion) -> Args {
+        fs::create_dir_all(data_dir.join("BTN vs BB/33")).unwrap();
+        fs::write(
+            data_dir.join("BTN vs BB/33/after_check.txt"),
+            "Tree\tEquity(*)\tEV\tBet 18.15\tCheck\n\
+            Ks7d4c\t50\t30\t20\t80\n\
+            8s8d8c\t50\t-\t60\t40\n\
+            8s8d\t50\t30\t20\t80\n\
+            Kh7c4d\t50\t10\t20\t80\n\
+            As7d4c\t50\t10\t40\n\
+            Ts6s4d\t40\t20\t30\t70\n",
+        )
+        .unwrap();
+
+        Args {
+            weighting: Weighting::Uniform,
+            ingestion,
+            ..Args::for_test(data_dir)
+        }
+    }
+
+    #[test]
+    fn test_build_aggregation_corrupt_rows_strict() {
+        let test_dir = TestDir::new("corrupt-rows");
+        let args = corrupt_args(test_dir.path(), Ingestion::Strict);
+        let path = args.data_dir.join("BTN vs BB/33/after_check.txt");
 
-        let (datarows, considered_boards) =
-            (datarows, validate_identical_and_get(considered_boards));
+        let err = build_aggregation(args).err().unwrap();
+        let Error::Report { location, source } = err else {
+            panic!("expected a report error, got {err}");
+        };
+        assert_eq!(
+            *location,
+            Location::new(&path).at_line(3, "8s8d8c\t50\t-\t60\t40")
+        );
+        assert_eq!(
+            source,
+            SchemaError::InvalidCell {
+                column: String::from("EV"),
+                value: String::from("-"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_aggregation_corrupt_rows_lenient() {
+        let test_dir = TestDir::new("corrupt-rows");
+        let args = corrupt_args(test_dir.path(), Ingestion::Lenient);
+        let location = Location::new(&args.data_dir.join("BTN vs BB/33/after_check.txt"));
+
+        let aggregation = build_aggregation(args).unwrap();
+
+        assert_eq!(
+            aggregation.boards,
+            vec![
+                Board::try_from("Ks7d4c").unwrap(),
+                Board::try_from("Ts6s4d").unwrap()
+            ]
+        );
+        assert_eq!(aggregation.data_rows[0].ev, 25.0);
+        assert_eq!(
+            aggregation.warnings[0],
+            format!(
+                "Skipped 4 of 6 rows of {location}: 1 non-numeric cell, 1 unparsable board, \
+                1 duplicate board, 1 missing column (lines 3, 4, 5, 6)"
+            )
+        );
+    }
+
+    #[test]
+    fn test_build_aggregation_combinatorial_weighting() {
+        let args = Args {
+            pair: vec![BoardPair::Paired, BoardPair::Trips],
+            betsizes: vec![Betsize::Percent(33)],
+            ..Args::for_test(data_dir())
+        };
+
+        let aggregation = build_aggregation(args).unwrap();
+        let row = &aggregation.data_rows[0];
+
+        // 8s8d8c stands for 4 flops, 8s8d6d and As5s5d for 12 each
+        let expected_eq = (48.567 * 4.0 + 66.065 * 12.0 + 81.424 * 12.0) / 28.0;
+        let expected_bet = (81.582 * 4.0 + 35.860 * 12.0 + 7.024 * 12.0) / 28.0;
+
+        assert!((row.eq - expected_eq).abs() < 0.001);
+        assert!((row.freq(&Action::Bet) - expected_bet).abs() < 0.001);
+    }
+
+    fn mismatched_args(data_dir: &Path, board_set_policy: BoardSetPolicy) -> Args {
+        let header = "Tree\tEquity(*)\tEV\tBet 18.15\tCheck\n";
+
+        fs::create_dir_all(data_dir.join("BTN vs BB/33")).unwrap();
+        fs::create_dir_all(data_dir.join("BTN vs BB/75")).unwrap();
+        fs::write(
+            data_dir.join("BTN vs BB/33/after_check.txt"),
+            format!("{header}Ks7d4c\t50\t30\t20\t80\n8s8d8c\t50\t10\t60\t40\n"),
+        )
+        .unwrap();
+        fs::write(
+            data_dir.join("BTN vs BB/75/after_check.txt"),
+            format!("{header}Kh7c4d\t50\t20\t10\t90\n"),
+        )
+        .unwrap();
+
+        Args {
+            weighting: Weighting::Uniform,
+            board_set_policy,
+            ..Args::for_test(data_dir)
+        }
+    }
+
+    #[test]
+    fn test_build_aggregation_mismatched_abort() {
+        let test_dir = TestDir::new("mismatched-boards");
+        let err = build_aggregation(mismatched_args(test_dir.path(), BoardSetPolicy::Abort))
+            .err()
+            .unwrap();
+
+        assert!(err.to_string().ends_with("\nBoard 8d8c8s only in sizes 33"));
+    }
+
+    #[test]
+    fn test_build_aggregation_mismatched_shared() {
+        let test_dir = TestDir::new("mismatched-boards");
+        let aggregation =
+            build_aggregation(mismatched_args(test_dir.path(), BoardSetPolicy::Shared)).unwrap();
+
+        assert_eq!(aggregation.boards, vec![Board::try_from("Ks7d4c").unwrap()]);
+        assert_eq!(aggregation.data_rows[0].ev, 30.0);
+        assert!(aggregation
+            .warnings
+            .contains(&String::from("Board 8d8c8s only in sizes 33")));
+    }
+
+    #[test]
+    fn test_build_aggregation_mismatched_own() {
+        let test_dir = TestDir::new("mismatched-boards");
+        let aggregation =
+            build_aggregation(mismatched_args(test_dir.path(), BoardSetPolicy::Own)).unwrap();
+
+        assert_eq!(aggregation.boards.len(), 2);
+        assert_eq!(aggregation.data_rows[0].ev, 20.0);
+        assert_eq!(aggregation.data_rows[1].ev, 20.0);
+        assert!(aggregation
+            .warnings
+            .contains(&String::from("Each size is aggregated over its own boards")));
+    }
+
+    #[test]
+    fn test_get_available_betsizes() {
+        let positions = Positions::new(Position::Btn, Position::BB).unwrap();
+        let size_dirs = get_size_dirs(&positions, Path::new(DATA_DIR)).unwrap();
+
+        assert_eq!(
+            get_available_betsizes(&size_dirs),
+            vec![
+                Betsize::Percent(33),
+                Betsize::Percent(50),
+                Betsize::Percent(75),
+                Betsize::Percent(150),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_pos_dir_no_match() {
+        let positions = Positions::new(Position::SB, Position::BB).unwrap();
+
+        let err = find_pos_dir(Path::new(DATA_DIR), &positions).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "No spot directory found for SB vs BB (IP BB, OOP SB). \
+            Candidates: BTN vs BB, CO vs BB, HJ vs BB, LJ vs BB"
+        );
+    }
+
+    #[test]
+    fn test_find_pos_dir_ambiguous() {
+        let test_dir = TestDir::new("ambiguous-spots");
+        let data_dir = test_dir.path();
+        fs::create_dir_all(data_dir.join("BTN vs BB")).unwrap();
+        fs::create_dir_all(data_dir.join("BB vs BTN")).unwrap();
+        fs::create_dir_all(data_dir.join("SB vs BB")).unwrap();
+
+        let positions = Positions::new(Position::Btn, Position::BB).unwrap();
+        let err = find_pos_dir(data_dir, &positions).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Ambiguous spot directories for BTN vs BB. Candidates: BB vs BTN, BTN vs BB"
+        );
+
+        let positions = Positions::new(Position::BB, Position::SB).unwrap();
+        let dir = find_pos_dir(data_dir, &positions).unwrap();
+        assert_eq!(get_name(&dir.path()), "SB vs BB");
+    }
+
+    #[test]
+    fn test_build_aggregation_1() {
+        let args = Args {
+            betsizes: vec![
+                Betsize::Percent(33),
+                Betsize::Percent(50),
+                Betsize::Percent(75),
+                Betsize::Percent(150),
+            ],
+            weighting: Weighting::Uniform,
+            ..Args::for_test(data_dir())
+        };
+
+        let aggregation = build_aggregation(args).unwrap();
+        let (datarows, considered_boards) = (aggregation.data_rows, aggregation.boards);
 
         let expected_datarows = [
             DataRow {
-                size: Some(Betsize::Size33),
+                size: Some(Betsize::Percent(33)),
                 eq: 60.755173,
                 ev: 28.557,
-                bet_freq: 28.126001,
-                check_freq: 65.877335,
+                freqs: bet_check_freqs(18.15, 28.126001, 65.877335),
             },
             DataRow {
-                size: Some(Betsize::Size50),
+                size: Some(Betsize::Percent(50)),
                 eq: 52.90833,
                 ev: 41.9065,
-                bet_freq: 28.626001,
-                check_freq: 75.374504,
+                freqs: bet_check_freqs(27.5, 28.626001, 75.374504),
             },
             DataRow {
-                size: Some(Betsize::Size75),
+                size: Some(Betsize::Percent(75)),
                 eq: 61.075,
                 ev: 39.07317,
-                bet_freq: 21.626001,
-                check_freq: 69.04117,
+                freqs: bet_check_freqs(41.25, 21.626001, 69.04117),
             },
             DataRow {
-                size: Some(Betsize::Size150),
+                size: Some(Betsize::Percent(150)),
                 eq: 55.74167,
                 ev: 31.739836,
-                bet_freq: 27.459335,
-                check_freq: 71.70783,
+                freqs: bet_check_freqs(82.5, 27.459335, 71.70783),
             },
         ];
 
@@ -277,45 +1083,34 @@ mod tests {
     }
 
     #[test]
-    fn test_build_data_rows_with_boards_2() {
+    fn test_build_aggregation_2() {
         let args = Args {
             positions: Positions {
                 ip: Position::CO,
                 oop: Position::BB,
             },
             pair: vec![BoardPair::Unpaired],
-            suits: Vec::new(),
             heights: vec![BoardHeight::SingleBW, BoardHeight::Middling],
-            actions: vec![Action::Check],
-            betsizes: vec![Betsize::Size33, Betsize::Size75],
-            connections: Vec::new(),
+            betsizes: vec![Betsize::Percent(33), Betsize::Percent(75)],
+            weighting: Weighting::Uniform,
+            ..Args::for_test(data_dir())
         };
 
-        let size_dirs = get_size_dirs(&args.positions, DATA_DIR);
-
-        let (datarows, considered_boards): (Vec<DataRow>, Vec<Vec<Board>>) = args
-            .betsizes
-            .iter()
-            .map(|betsize| build_data_row_with_boards(betsize, &size_dirs, &args))
-            .unzip();
-
-        let (datarows, considered_boards) =
-            (datarows, validate_identical_and_get(considered_boards));
+        let aggregation = build_aggregation(args).unwrap();
+        let (datarows, considered_boards) = (aggregation.data_rows, aggregation.boards);
 
         let expected_datarows = [
             DataRow {
-                size: Some(Betsize::Size33),
+                size: Some(Betsize::Percent(33)),
                 eq: 56.47,
                 ev: 41.996334,
-                bet_freq: 18.763334,
-                check_freq: 58.896336,
+                freqs: bet_check_freqs(18.15, 18.763334, 58.896336),
             },
             DataRow {
-                size: Some(Betsize::Size75),
+                size: Some(Betsize::Percent(75)),
                 eq: 42.803333,
                 ev: 47.996338,
-                bet_freq: 20.43,
-                check_freq: 58.563004,
+                freqs: bet_check_freqs(41.25, 20.43, 58.563004),
             },
         ];
 
@@ -335,7 +1130,7 @@ mod tests {
     }
 
     #[test]
-    fn test_build_data_rows_with_boards_3() {
+    fn test_build_aggregation_3() {
         let args = Args {
             positions: Positions {
                 ip: Position::HJ,
@@ -343,37 +1138,26 @@ mod tests {
             },
             pair: vec![BoardPair::Paired, BoardPair::Trips],
             suits: vec![BoardSuit::Twotone],
-            heights: Vec::new(),
-            actions: vec![Action::Check],
-            betsizes: vec![Betsize::Size50, Betsize::Size150],
-            connections: Vec::new(),
+            betsizes: vec![Betsize::Percent(50), Betsize::Percent(150)],
+            weighting: Weighting::Uniform,
+            ..Args::for_test(data_dir())
         };
 
-        let size_dirs = get_size_dirs(&args.positions, DATA_DIR);
-
-        let (datarows, considered_boards): (Vec<DataRow>, Vec<Vec<Board>>) = args
-            .betsizes
-            .iter()
-            .map(|betsize| build_data_row_with_boards(betsize, &size_dirs, &args))
-            .unzip();
-
-        let (datarows, considered_boards) =
-            (datarows, validate_identical_and_get(considered_boards));
+        let aggregation = build_aggregation(args).unwrap();
+        let (datarows, considered_boards) = (aggregation.data_rows, aggregation.boards);
 
         let expected_datarows = [
             DataRow {
-                size: Some(Betsize::Size50),
+                size: Some(Betsize::Percent(50)),
                 eq: 72.239,
                 ev: 27.4945,
-                bet_freq: 23.442001,
-                check_freq: 80.569,
+                freqs: bet_check_freqs(27.5, 23.442001, 80.569),
             },
             DataRow {
-                size: Some(Betsize::Size150),
+                size: Some(Betsize::Percent(150)),
                 eq: 69.239,
                 ev: 23.9945,
-                bet_freq: 19.442001,
-                check_freq: 83.569,
+                freqs: bet_check_freqs(82.5, 19.442001, 83.569),
             },
         ];
 
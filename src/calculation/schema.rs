@@ -0,0 +1,314 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::poker::action::Action;
+
+const TREE_COLUMN: &str = "Tree";
+const EQUITY_COLUMNS: [&str; 2] = ["Equity(*)", "Equity"];
+const EV_COLUMN: &str = "EV";
+const WEIGHT_COLUMN: &str = "Weight";
+const ACTION_COLUMN: &str = "Bet/Check/Raise/Call/Fold";
+
+#[derive(Debug, PartialEq)]
+pub enum SchemaError {
+    MissingColumn(&'static str),
+    MissingCell(String),
+    InvalidCell { column: String, value: String },
+    InvalidBoard(String),
+    DuplicateBoard { board: String, other: String },
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::MissingColumn(column) => {
+                write!(f, "required column '{column}' is missing from the header")
+            }
+            SchemaError::MissingCell(column) => {
+                write!(f, "line has no value for column '{column}'")
+            }
+            SchemaError::InvalidCell { column, value } => {
+                write!(f, "value '{value}' of column '{column}' is not a number")
+            }
+            SchemaError::InvalidBoard(value) => write!(f, "'{value}' is not a valid board"),
+            SchemaError::DuplicateBoard { board, other } => write!(
+                f,
+                "boards {other} and {board} are duplicates up to suit isomorphism"
+            ),
+        }
+    }
+}
+
+impl Error for SchemaError {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActionColumn {
+    pub index: usize,
+    pub action: Action,
+    pub chips: Option<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnSchema {
+    pub names: Vec<String>,
+    pub tree: usize,
+    pub equity: usize,
+    pub ev: usize,
+    pub actions: Vec<ActionColumn>,
+    pub weight: Option<usize>,
+    pub extra: Vec<usize>,
+}
+
+impl ColumnSchema {
+    pub fn parse(header: &str) -> Result<ColumnSchema, SchemaError> {
+        let names: Vec<String> = header
+            .split('\t')
+            .map(|name| name.trim().to_string())
+            .collect();
+
+        let tree = find_column(&names, TREE_COLUMN, |name| name == TREE_COLUMN)?;
+        let equity = find_column(&names, EQUITY_COLUMNS[0], |name| {
+            EQUITY_COLUMNS.contains(&name)
+        })?;
+        let ev = find_column(&names, EV_COLUMN, |name| name == EV_COLUMN)?;
+
+        let actions: Vec<ActionColumn> = names
+            .iter()
+            .enumerate()
+            .filter_map(|(index, name)| {
+                parse_action_column(name).map(|(action, chips)| ActionColumn {
+                    index,
+                    action,
+                    chips,
+                })
+            })
+            .collect();
+
+        if actions.is_empty() {
+            return Err(SchemaError::MissingColumn(ACTION_COLUMN));
+        }
+
+        let weight = find_column(&names, WEIGHT_COLUMN, |name| {
+            name.eq_ignore_ascii_case(WEIGHT_COLUMN)
+        })
+        .ok();
+
+        let extra = (0..names.len())
+            .filter(|index| {
+                ![tree, equity, ev].contains(index)
+                    && weight != Some(*index)
+                    && actions.iter().all(|column| column.index != *index)
+                    && !names[*index].is_empty()
+            })
+            .collect();
+
+        Ok(ColumnSchema {
+            names,
+            tree,
+            equity,
+            ev,
+            actions,
+            weight,
+            extra,
+        })
+    }
+
+    // The size of the bet this report was solved for, if it offers exactly one
+    pub fn bet_chips(&self) -> Option<f32> {
+        let mut bets = self
+            .actions
+            .iter()
+            .filter(|column| column.action == Action::Bet);
+
+        match (bets.next(), bets.next()) {
+            (Some(bet), None) => bet.chips,
+            _ => None,
+        }
+    }
+
+    pub fn cell<'a>(&self, cells: &[&'a str], index: usize) -> Result<&'a str, SchemaError> {
+        cells
+            .get(index)
+            .map(|cell| cell.trim())
+            .filter(|cell| !cell.is_empty())
+            .ok_or_else(|| SchemaError::MissingCell(self.names[index].clone()))
+    }
+
+    pub fn number(&self, cells: &[&str], index: usize) -> Result<f32, SchemaError> {
+        let value = self.cell(cells, index)?;
+
+        value.parse().map_err(|_| SchemaError::InvalidCell {
+            column: self.names[index].clone(),
+            value: value.to_string(),
+        })
+    }
+}
+
+fn find_column(
+    names: &[String],
+    required: &'static str,
+    matches: impl Fn(&str) -> bool,
+) -> Result<usize, SchemaError> {
+    names
+        .iter()
+        .position(|name| matches(name))
+        .ok_or(SchemaError::MissingColumn(required))
+}
+
+/*
+ * Action columns are named like the action ("Check", "Call", "Fold"),
+ * optionally followed by an amount in chips ("Bet 18.15", "Raise 60")
+ */
+fn parse_action_column(name: &str) -> Option<(Action, Option<f32>)> {
+    let (action_name, amount) = match name.split_once(' ') {
+        Some((action_name, amount)) => (action_name, Some(amount)),
+        None => (name, None),
+    };
+
+    let action = Action::from_long_str(&action_name.to_lowercase()).ok()?;
+
+    match amount {
+        None => Some((action, None)),
+        Some(amount) => amount
+            .trim()
+            .parse()
+            .ok()
+            .map(|chips| (action, Some(chips))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_schema_parse() {
+        let schema = ColumnSchema::parse("Tree	Equity(*)	EV	Bet 18.15	Check").unwrap();
+
+        assert_eq!(schema.tree, 0);
+        assert_eq!(schema.equity, 1);
+        assert_eq!(schema.ev, 2);
+        assert_eq!(
+            schema.actions,
+            vec![
+                ActionColumn {
+                    index: 3,
+                    action: Action::Bet,
+                    chips: Some(18.15)
+                },
+                ActionColumn {
+                    index: 4,
+                    action: Action::Check,
+                    chips: None
+                },
+            ]
+        );
+        assert_eq!(schema.bet_chips(), Some(18.15));
+        assert_eq!(schema.weight, None);
+        assert!(schema.extra.is_empty());
+    }
+
+    #[test]
+    fn test_column_schema_parse_reordered_with_extra() {
+        let schema = ColumnSchema::parse("Tree	EV	Check	Weight	Bet 82.5	Equity(*)	Total").unwrap();
+
+        assert_eq!(schema.tree, 0);
+        assert_eq!(schema.ev, 1);
+        assert_eq!(schema.equity, 5);
+        assert_eq!(schema.actions.len(), 2);
+        assert_eq!(schema.actions[0].index, 2);
+        assert_eq!(schema.actions[1].index, 4);
+        assert_eq!(schema.bet_chips(), Some(82.5));
+        assert_eq!(schema.weight, Some(3));
+        assert_eq!(schema.extra, vec![6]);
+    }
+
+    #[test]
+    fn test_column_schema_parse_several_bets() {
+        let schema =
+            ColumnSchema::parse("Tree	Equity(*)	EV	Bet 18.15	Bet 41.25	Bet 82.5	Check").unwrap();
+
+        let chips: Vec<_> = schema.actions.iter().map(|column| column.chips).collect();
+        assert_eq!(chips, vec![Some(18.15), Some(41.25), Some(82.5), None]);
+        assert_eq!(schema.bet_chips(), None);
+    }
+
+    #[test]
+    fn test_column_schema_parse_facing_bet() {
+        let schema = ColumnSchema::parse("Tree	Equity(*)	EV	Raise 60	Call	Fold").unwrap();
+
+        let actions: Vec<_> = schema
+            .actions
+            .iter()
+            .map(|column| (&column.action, column.chips))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                (&Action::Raise, Some(60.0)),
+                (&Action::Call, None),
+                (&Action::Fold, None)
+            ]
+        );
+        assert_eq!(schema.bet_chips(), None);
+    }
+
+    #[test]
+    fn test_column_schema_parse_missing_column() {
+        assert_eq!(
+            ColumnSchema::parse("Tree	Equity(*)	Bet 18.15	Check"),
+            Err(SchemaError::MissingColumn("EV"))
+        );
+        assert_eq!(
+            ColumnSchema::parse("Tree	Equity(*)	EV	Better"),
+            Err(SchemaError::MissingColumn("Bet/Check/Raise/Call/Fold"))
+        );
+        assert_eq!(
+            ColumnSchema::parse(""),
+            Err(SchemaError::MissingColumn("Tree"))
+        );
+    }
+
+    #[test]
+    fn test_parse_action_column() {
+        assert_eq!(
+            parse_action_column("Bet 18.15"),
+            Some((Action::Bet, Some(18.15)))
+        );
+        assert_eq!(parse_action_column("Bet"), Some((Action::Bet, None)));
+        assert_eq!(
+            parse_action_column("Raise 60"),
+            Some((Action::Raise, Some(60.0)))
+        );
+        assert_eq!(parse_action_column("Check"), Some((Action::Check, None)));
+        assert_eq!(parse_action_column("Fold"), Some((Action::Fold, None)));
+        assert_eq!(parse_action_column("Bet abc"), None);
+        assert_eq!(parse_action_column("Better"), None);
+        assert_eq!(parse_action_column("Total"), None);
+    }
+
+    #[test]
+    fn test_column_schema_number() {
+        let schema = ColumnSchema::parse("Tree	Equity(*)	EV	Bet 18.15	Check").unwrap();
+        let cells = ["8s8d8c", "48.567", "abc", ""];
+
+        assert_eq!(schema.number(&cells, 1), Ok(48.567));
+        assert_eq!(
+            schema.number(&cells, 2),
+            Err(SchemaError::InvalidCell {
+                column: "EV".to_string(),
+                value: "abc".to_string()
+            })
+        );
+        assert_eq!(
+            schema.number(&cells, 3),
+            Err(SchemaError::MissingCell("Bet 18.15".to_string()))
+        );
+        assert_eq!(
+            schema.number(&cells, 4),
+            Err(SchemaError::MissingCell("Check".to_string()))
+        );
+    }
+}
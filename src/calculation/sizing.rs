@@ -0,0 +1,149 @@
+use crate::poker::betsize::Betsize;
+
+// Percentage points a header may deviate from its directory label, e.g. 66 for 2/3 pot
+const PERCENT_TOLERANCE: f32 = 1.0;
+
+#[derive(Debug, PartialEq)]
+pub struct BetSizing {
+    pub betsize: Betsize,
+    pub chips: f32,
+    pub percent: Option<f32>,
+}
+
+// Percent sizes needed to derive the pot, with fewer a mislabeled folder moves the median
+pub const MIN_POT_ESTIMATES: usize = 3;
+
+/*
+ * Every size file of a spot is solved with the same pot, so each labeled percentage
+ * gives one estimate of it. The median keeps a single mislabeled folder from skewing it.
+ */
+pub fn derive_pot(bet_chips: &[(Betsize, f32)]) -> Option<f32> {
+    let mut estimates: Vec<f32> = bet_chips
+        .iter()
+        .filter_map(|(betsize, chips)| match betsize {
+            Betsize::Percent(percent) => Some(chips * 100.0 / *percent as f32),
+            Betsize::AllIn => None,
+        })
+        .collect();
+
+    if estimates.len() < MIN_POT_ESTIMATES {
+        return None;
+    }
+
+    estimates.sort_by(|a, b| a.total_cmp(b));
+    let mid = estimates.len() / 2;
+
+    if estimates.len().is_multiple_of(2) {
+        Some((estimates[mid - 1] + estimates[mid]) / 2.0)
+    } else {
+        Some(estimates[mid])
+    }
+}
+
+/*
+ * Minimum defense frequency in percent against a bet of the given size. The bet is priced
+ * by the percent of the pot its header gives, or by its label when the header has no bet.
+ */
+pub fn mdf(betsize: &Betsize, sizings: &[BetSizing]) -> Option<f32> {
+    let percent = sizings
+        .iter()
+        .find(|sizing| sizing.betsize == *betsize)
+        .and_then(|sizing| sizing.percent)
+        .or_else(|| betsize.percent().map(|percent| percent as f32))?;
+
+    Some(100.0 * 100.0 / (100.0 + percent))
+}
+
+pub fn build_bet_sizings(bet_chips: Vec<(Betsize, f32)>, pot: Option<f32>) -> Vec<BetSizing> {
+    bet_chips
+        .into_iter()
+        .map(|(betsize, chips)| BetSizing {
+            betsize,
+            chips,
+            percent: pot.map(|pot| chips / pot * 100.0),
+        })
+        .collect()
+}
+
+pub fn validate_bet_sizings(sizings: &[BetSizing]) -> Vec<String> {
+    sizings
+        .iter()
+        .filter_map(|sizing| {
+            let label = sizing.betsize.percent()? as f32;
+            let percent = sizing.percent?;
+
+            ((percent - label).abs() > PERCENT_TOLERANCE).then(|| {
+                format!(
+                    "Size directory {} contains a bet of {} chips, which is {percent:.1}% of the pot",
+                    sizing.betsize, sizing.chips
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_pot() {
+        let bet_chips = vec![
+            (Betsize::Percent(33), 18.15),
+            (Betsize::Percent(50), 27.5),
+            (Betsize::Percent(75), 41.25),
+            (Betsize::Percent(150), 82.5),
+            (Betsize::AllIn, 500.0),
+        ];
+        assert_eq!(derive_pot(&bet_chips), Some(55.0));
+
+        let bet_chips = vec![
+            (Betsize::Percent(33), 18.15),
+            (Betsize::Percent(50), 27.5),
+            (Betsize::Percent(75), 82.5),
+        ];
+        assert_eq!(derive_pot(&bet_chips), Some(55.0));
+
+        let bet_chips = vec![(Betsize::Percent(33), 18.15), (Betsize::Percent(75), 82.5)];
+        assert_eq!(derive_pot(&bet_chips), None);
+        assert_eq!(derive_pot(&[(Betsize::AllIn, 500.0)]), None);
+        assert_eq!(derive_pot(&[]), None);
+    }
+
+    #[test]
+    fn test_mdf() {
+        assert_eq!(mdf(&Betsize::Percent(100), &[]), Some(50.0));
+        assert_eq!(mdf(&Betsize::Percent(50), &[]), Some(200.0 / 3.0));
+        assert_eq!(mdf(&Betsize::Percent(150), &[]), Some(40.0));
+        assert_eq!(mdf(&Betsize::AllIn, &[]), None);
+
+        // The header's bet wins over a mislabeled folder and prices an all-in
+        let bet_chips = vec![(Betsize::Percent(50), 55.0), (Betsize::AllIn, 82.5)];
+        let sizings = build_bet_sizings(bet_chips, Some(55.0));
+        assert_eq!(mdf(&Betsize::Percent(50), &sizings), Some(50.0));
+        assert_eq!(mdf(&Betsize::AllIn, &sizings), Some(40.0));
+        assert_eq!(mdf(&Betsize::Percent(150), &sizings), Some(40.0));
+
+        let sizings = build_bet_sizings(vec![(Betsize::Percent(50), 55.0)], None);
+        assert_eq!(mdf(&Betsize::Percent(50), &sizings), Some(200.0 / 3.0));
+    }
+
+    #[test]
+    fn test_validate_bet_sizings() {
+        let bet_chips = vec![
+            (Betsize::Percent(33), 18.15),
+            (Betsize::Percent(66), 36.67),
+            (Betsize::Percent(75), 82.5),
+            (Betsize::AllIn, 500.0),
+        ];
+
+        let sizings = build_bet_sizings(bet_chips, Some(55.0));
+        assert_eq!(sizings[0].percent, Some(33.0));
+        assert_eq!(sizings[2].percent, Some(150.0));
+
+        assert_eq!(
+            validate_bet_sizings(&sizings),
+            vec!["Size directory 75 contains a bet of 82.5 chips, which is 150.0% of the pot"]
+        );
+    }
+}
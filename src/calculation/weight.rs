@@ -0,0 +1,78 @@
+use std::str::FromStr;
+
+use crate::poker::{board::Board, ParseError};
+
+use super::schema::{ColumnSchema, SchemaError};
+
+#[derive(Debug, PartialEq)]
+pub enum Weighting {
+    Combinatorial,
+    Uniform,
+}
+
+impl FromStr for Weighting {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Weighting, ParseError> {
+        match s {
+            "C" => Ok(Weighting::Combinatorial),
+            "U" => Ok(Weighting::Uniform),
+            _ => Err(ParseError::str("weighting", s)),
+        }
+    }
+}
+
+/*
+ * A solved subset only names one board of each isomorphism class, so without
+ * a weight column of the export each board stands for all of its suit variants,
+ * e.g. a rainbow flop for 24 of the 22,100 flops and a monotone one for 4.
+ */
+pub fn get_weight(weight_column: Option<f32>, board: &Board, weighting: &Weighting) -> f32 {
+    match (weighting, weight_column) {
+        (Weighting::Uniform, _) => 1.0,
+        (Weighting::Combinatorial, Some(weight)) => weight,
+        (Weighting::Combinatorial, None) => board.num_isomorphic_variants() as f32,
+    }
+}
+
+// Value of the weight column of the line, if the export has one
+pub fn read_weight_column(
+    cells: &[&str],
+    schema: &ColumnSchema,
+) -> Result<Option<f32>, SchemaError> {
+    schema
+        .weight
+        .map(|index| schema.number(cells, index))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_weight() {
+        let board = Board::try_from("8s8d8c").unwrap();
+
+        assert_eq!(get_weight(None, &board, &Weighting::Combinatorial), 4.0);
+        assert_eq!(get_weight(None, &board, &Weighting::Uniform), 1.0);
+    }
+
+    #[test]
+    fn test_get_weight_column() {
+        let schema = ColumnSchema::parse("Tree	Weight	Equity(*)	EV	Bet 18.15	Check").unwrap();
+        let cells = ["8s8d8c", "0.5", "48.567", "36.457", "81.582", "10.43"];
+        let board = Board::try_from("8s8d8c").unwrap();
+
+        let weight_column = read_weight_column(&cells, &schema).unwrap();
+        assert_eq!(weight_column, Some(0.5));
+        assert_eq!(
+            get_weight(weight_column, &board, &Weighting::Combinatorial),
+            0.5
+        );
+        assert_eq!(get_weight(weight_column, &board, &Weighting::Uniform), 1.0);
+
+        let schema = ColumnSchema::parse("Tree	Equity(*)	EV	Bet 18.15	Check").unwrap();
+        assert_eq!(read_weight_column(&cells, &schema), Ok(None));
+    }
+}
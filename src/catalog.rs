@@ -0,0 +1,244 @@
+use std::{collections::HashMap, fs::DirEntry, path::Path, str::FromStr};
+
+use crate::{
+    error::Error,
+    files,
+    poker::{action::Action, betsize::Betsize, position::Positions},
+};
+
+pub struct Catalog {
+    pub spots: Vec<Spot>,
+    pub warnings: Vec<String>,
+}
+
+pub struct Spot {
+    pub name: String,
+    pub sizes: Vec<SizeDir>,
+}
+
+pub struct SizeDir {
+    pub name: String,
+    pub action_files: Vec<String>,
+}
+
+pub fn build_catalog(data_dir: &Path) -> Result<Catalog, Error> {
+    let mut warnings = Vec::new();
+
+    warn_unexpected_files(data_dir, &mut warnings)?;
+
+    let mut spots: Vec<Spot> = files::get_dirs(data_dir)?
+        .iter()
+        .map(|spot_dir| build_spot(spot_dir, &mut warnings))
+        .collect::<Result<_, _>>()?;
+    spots.sort_by(|spot1, spot2| spot1.name.cmp(&spot2.name));
+
+    warn_duplicate_spots(&spots, &mut warnings);
+
+    Ok(Catalog { spots, warnings })
+}
+
+fn build_spot(spot_dir: &DirEntry, warnings: &mut Vec<String>) -> Result<Spot, Error> {
+    let spot_path = spot_dir.path();
+    let name = files::get_name(&spot_path);
+
+    if Positions::from_str(&name).is_err() {
+        warnings.push(format!(
+            "Spot directory '{name}' is not of the form '<position> vs <position>'"
+        ));
+    }
+
+    warn_unexpected_files(&spot_path, warnings)?;
+
+    let mut sizes: Vec<SizeDir> = files::get_dirs(&spot_path)?
+        .iter()
+        .map(|size_dir| build_size_dir(&name, size_dir, warnings))
+        .collect::<Result<_, _>>()?;
+    sizes.sort_by_key(|size| (Betsize::from_str(&size.name).ok(), size.name.clone()));
+
+    if sizes.is_empty() {
+        warnings.push(format!(
+            "Spot directory '{name}' contains no size directories"
+        ));
+    }
+
+    Ok(Spot { name, sizes })
+}
+
+fn build_size_dir(
+    spot_name: &str,
+    size_dir: &DirEntry,
+    warnings: &mut Vec<String>,
+) -> Result<SizeDir, Error> {
+    let size_path = size_dir.path();
+    let name = files::get_name(&size_path);
+
+    if Betsize::from_str(&name).is_err() {
+        warnings.push(format!(
+            "Size directory '{spot_name}/{name}' is not a known bet size"
+        ));
+    }
+
+    for dir in files::get_dirs(&size_path)? {
+        warnings.push(format!(
+            "Unexpected directory '{spot_name}/{name}/{}'",
+            files::get_name(&dir.path())
+        ));
+    }
+
+    let mut action_files: Vec<String> = files::get_files(&size_path)?
+        .iter()
+        .map(|file| files::get_name(&file.path()))
+        .collect();
+    action_files.sort();
+
+    for action_file in &action_files {
+        if parse_action_file_name(action_file).is_none() {
+            warnings.push(format!(
+                "File '{spot_name}/{name}/{action_file}' is not a known action file"
+            ));
+        }
+    }
+
+    if action_files.is_empty() {
+        warnings.push(format!(
+            "Size directory '{spot_name}/{name}' contains no action files"
+        ));
+    }
+
+    Ok(SizeDir { name, action_files })
+}
+
+fn warn_unexpected_files(dir: &Path, warnings: &mut Vec<String>) -> Result<(), Error> {
+    for file in files::get_files(dir)? {
+        warnings.push(format!(
+            "Unexpected file '{}'",
+            file.path().to_string_lossy()
+        ));
+    }
+
+    Ok(())
+}
+
+fn warn_duplicate_spots(spots: &[Spot], warnings: &mut Vec<String>) {
+    let mut spots_by_positions: HashMap<Positions, Vec<&str>> = HashMap::new();
+
+    for spot in spots {
+        if let Ok(positions) = Positions::from_str(&spot.name) {
+            spots_by_positions
+                .entry(positions)
+                .or_default()
+                .push(&spot.name);
+        }
+    }
+
+    let mut duplicates: Vec<_> = spots_by_positions
+        .into_iter()
+        .filter(|(_, names)| names.len() > 1)
+        .collect();
+    duplicates.sort_by_key(|(positions, _)| positions.to_string());
+
+    for (positions, names) in duplicates {
+        warnings.push(format!(
+            "Spot {positions} is ambiguous: {}",
+            names.join(", ")
+        ));
+    }
+}
+
+// "root" holds the first decision of the spot, "after_<action>_..." the nodes behind it
+pub fn parse_action_file_name(name: &str) -> Option<Vec<Action>> {
+    if name == "root" {
+        return Some(Vec::new());
+    }
+
+    let mut split = name.split('_');
+
+    if split.next() != Some("after") {
+        return None;
+    }
+
+    let actions: Vec<Action> = split
+        .map(Action::from_long_str)
+        .collect::<Result<_, _>>()
+        .ok()?;
+
+    (!actions.is_empty()).then_some(actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_catalog() {
+        let catalog = build_catalog(Path::new("./test_data")).unwrap();
+
+        let spot_names: Vec<_> = catalog.spots.iter().map(|spot| &spot.name).collect();
+        assert_eq!(
+            spot_names,
+            vec!["BTN vs BB", "CO vs BB", "HJ vs BB", "LJ vs BB"]
+        );
+
+        for spot in &catalog.spots {
+            let size_names: Vec<_> = spot.sizes.iter().map(|size| &size.name).collect();
+            assert_eq!(size_names, vec!["33", "50", "75", "150"]);
+
+            for size in &spot.sizes {
+                let has_root = spot.name == "CO vs BB" && ["33", "50"].contains(&&size.name[..]);
+                let expected = if has_root {
+                    vec!["after_check", "root"]
+                } else {
+                    vec!["after_check"]
+                };
+
+                assert_eq!(size.action_files, expected);
+            }
+        }
+
+        assert_eq!(catalog.warnings.len(), 3);
+        assert!(catalog
+            .warnings
+            .iter()
+            .all(|warning| warning.starts_with("Unexpected file")));
+    }
+
+    #[test]
+    fn test_build_catalog_missing_data_dir() {
+        let err = build_catalog(Path::new("./missing_data")).err().unwrap();
+        assert!(err
+            .to_string()
+            .starts_with("Could not read ./missing_data: "));
+    }
+
+    #[test]
+    fn test_warn_duplicate_spots() {
+        let spots = ["BB vs BTN", "BTN vs BB", "SB vs BB", "old"].map(|name| Spot {
+            name: name.to_string(),
+            sizes: Vec::new(),
+        });
+
+        let mut warnings = Vec::new();
+        warn_duplicate_spots(&spots, &mut warnings);
+
+        assert_eq!(
+            warnings,
+            vec!["Spot BTN vs BB is ambiguous: BB vs BTN, BTN vs BB"]
+        );
+    }
+
+    #[test]
+    fn test_parse_action_file_name() {
+        assert_eq!(
+            parse_action_file_name("after_check"),
+            Some(vec![Action::Check])
+        );
+        assert_eq!(
+            parse_action_file_name("after_check_bet"),
+            Some(vec![Action::Check, Action::Bet])
+        );
+        assert_eq!(parse_action_file_name("root"), Some(Vec::new()));
+        assert_eq!(parse_action_file_name("after"), None);
+        assert_eq!(parse_action_file_name("after_donk"), None);
+        assert_eq!(parse_action_file_name("notes"), None);
+    }
+}
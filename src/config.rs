@@ -0,0 +1,108 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+pub const DATA_DIR_ENV: &str = "GTO_PARSE_DATA_DIR";
+const DATA_DIR_KEY: &str = "data_dir";
+const CONFIG_FILE_NAME: &str = "gto-parse.conf";
+const DEFAULT_DATA_DIR: &str = "./data";
+
+/*
+ * Precedence of the data root (highest first):
+ * 1. -d command line option
+ * 2. GTO_PARSE_DATA_DIR environment variable
+ * 3. data_dir entry of ./gto-parse.conf
+ * 4. data_dir entry of ~/.config/gto-parse/gto-parse.conf
+ * 5. ./data
+ */
+pub fn resolve_data_dir(cli_data_dir: Option<PathBuf>) -> PathBuf {
+    let env_data_dir = env::var(DATA_DIR_ENV).ok().filter(|dir| !dir.is_empty());
+    let config_data_dir = config_file_paths()
+        .iter()
+        .find_map(|path| read_config_data_dir(path));
+
+    choose_data_dir(cli_data_dir, env_data_dir, config_data_dir)
+}
+
+fn choose_data_dir(
+    cli_data_dir: Option<PathBuf>,
+    env_data_dir: Option<String>,
+    config_data_dir: Option<String>,
+) -> PathBuf {
+    cli_data_dir
+        .or(env_data_dir.map(PathBuf::from))
+        .or(config_data_dir.map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_DIR))
+}
+
+fn config_file_paths() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(CONFIG_FILE_NAME)];
+
+    if let Some(home) = env::var_os("HOME") {
+        paths.push(
+            Path::new(&home)
+                .join(".config")
+                .join("gto-parse")
+                .join(CONFIG_FILE_NAME),
+        );
+    }
+
+    paths
+}
+
+fn read_config_data_dir(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| parse_config_value(&content, DATA_DIR_KEY))
+}
+
+fn parse_config_value(content: &str, key: &str) -> Option<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .find(|(line_key, _)| line_key.trim() == key)
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_data_dir_precedence() {
+        let cli = Some(PathBuf::from("/cli"));
+        let env = Some("/env".to_string());
+        let config = Some("/config".to_string());
+
+        assert_eq!(
+            choose_data_dir(cli.clone(), env.clone(), config.clone()),
+            PathBuf::from("/cli")
+        );
+        assert_eq!(
+            choose_data_dir(None, env.clone(), config.clone()),
+            PathBuf::from("/env")
+        );
+        assert_eq!(
+            choose_data_dir(None, None, config),
+            PathBuf::from("/config")
+        );
+        assert_eq!(choose_data_dir(None, None, None), PathBuf::from("./data"));
+    }
+
+    #[test]
+    fn test_parse_config_value() {
+        let content = "# solution sets\n\nother = 1\ndata_dir = \"/mnt/shared/solves\"\n";
+        assert_eq!(
+            parse_config_value(content, "data_dir"),
+            Some("/mnt/shared/solves".to_string())
+        );
+
+        assert_eq!(parse_config_value("data_dir =", "data_dir"), None);
+        assert_eq!(parse_config_value("# data_dir = /x", "data_dir"), None);
+        assert_eq!(parse_config_value("", "data_dir"), None);
+    }
+}
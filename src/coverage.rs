@@ -0,0 +1,291 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use crate::{
+    args::Args,
+    calculation::{self, ingestion::Ingestion},
+    error::Error,
+    files,
+    poker::{
+        betsize::Betsize,
+        board::{
+            enumeration::{self, NUM_FLOPS},
+            height::BoardHeight,
+            pair::BoardPair,
+            suit::BoardSuit,
+            texture::Texture,
+            Board,
+        },
+    },
+};
+
+// A texture class is under-represented below this share of its true frequency
+const UNDER_REPRESENTED_RATIO: f32 = 0.75;
+
+pub struct Coverage {
+    pub num_canonical_flops: usize,
+    pub sizes: Vec<SizeCoverage>,
+    pub under_represented: Vec<TextureShare>,
+    pub warnings: Vec<String>,
+}
+
+pub struct SizeCoverage {
+    pub name: String,
+    pub num_flops: usize,
+    // Boards present in other size directories of the spot, but not in this one
+    pub missing: Vec<Board>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TextureShare {
+    pub class: &'static str,
+    pub dataset_percent: f32,
+    pub true_percent: f32,
+}
+
+struct TextureClass {
+    name: &'static str,
+    matches: Box<dyn Fn(&Texture) -> bool>,
+}
+
+pub fn build_coverage(args: &Args) -> Result<Coverage, Error> {
+    let canonical_flops = enumeration::get_canonical_flops();
+
+    let size_dirs = calculation::get_size_dirs(&args.positions, &args.data_dir)?;
+    let paths: Vec<PathBuf> = size_dirs
+        .iter()
+        .map(|size_dir| Ok(calculation::get_action_file_in_dir(size_dir, &args.actions)?.path()))
+        .collect::<Result<_, Error>>()?;
+
+    // Every board of each size file, keyed by its canonical form
+    let reports = calculation::load_reports(&paths, &Ingestion::Strict, args.cache_dir.as_deref())?;
+    let warnings = calculation::get_cache_warning(&reports)
+        .into_iter()
+        .collect();
+    let mut boards_by_size: Vec<(String, HashMap<Board, Board>)> = size_dirs
+        .iter()
+        .zip(reports)
+        .map(|(size_dir, loaded)| {
+            let boards = loaded
+                .report
+                .rows
+                .into_iter()
+                .map(|row| (row.board.canonical(), row.board))
+                .collect();
+
+            (files::get_name(&size_dir.path()), boards)
+        })
+        .collect();
+    boards_by_size.sort_by_key(|(name, _)| (Betsize::from_str(name).ok(), name.clone()));
+
+    let all_boards: HashMap<&Board, &Board> = boards_by_size
+        .iter()
+        .flat_map(|(_, boards)| boards.iter())
+        .collect();
+
+    let sizes: Vec<SizeCoverage> = boards_by_size
+        .iter()
+        .map(|(name, boards)| {
+            let mut missing: Vec<Board> = all_boards
+                .iter()
+                .filter(|(canonical, _)| !boards.contains_key(**canonical))
+                .map(|(_, board)| (*board).clone())
+                .collect();
+            missing.sort_by_key(|board| board.to_string());
+
+            SizeCoverage {
+                name: name.clone(),
+                num_flops: boards.keys().filter(|board| board.num_cards() == 3).count(),
+                missing,
+            }
+        })
+        .collect();
+
+    let dataset_flops: HashSet<&Board> = all_boards
+        .keys()
+        .copied()
+        .filter(|board| board.num_cards() == 3)
+        .collect();
+
+    Ok(Coverage {
+        num_canonical_flops: canonical_flops.len(),
+        sizes,
+        under_represented: get_under_represented(&dataset_flops, &canonical_flops),
+        warnings,
+    })
+}
+
+fn get_under_represented(
+    dataset_flops: &HashSet<&Board>,
+    canonical_flops: &[(Board, usize)],
+) -> Vec<TextureShare> {
+    if dataset_flops.is_empty() {
+        return Vec::new();
+    }
+
+    // Both shares are of all 22,100 flops, so each canonical flop counts as its variants
+    let dataset_textures: Vec<(Texture, usize)> = dataset_flops
+        .iter()
+        .map(|board| (Texture::of(board), board.num_isomorphic_variants()))
+        .collect();
+    let num_dataset_flops: usize = dataset_textures.iter().map(|(_, freq)| freq).sum();
+    let canonical_textures: Vec<(Texture, usize)> = canonical_flops
+        .iter()
+        .map(|(board, freq)| (Texture::of(board), *freq))
+        .collect();
+
+    get_texture_classes()
+        .iter()
+        .map(|class| {
+            let num_in_dataset: usize = dataset_textures
+                .iter()
+                .filter(|(texture, _)| (class.matches)(texture))
+                .map(|(_, freq)| freq)
+                .sum();
+            let num_flops: usize = canonical_textures
+                .iter()
+                .filter(|(texture, _)| (class.matches)(texture))
+                .map(|(_, freq)| freq)
+                .sum();
+
+            TextureShare {
+                class: class.name,
+                dataset_percent: num_in_dataset as f32 / num_dataset_flops as f32 * 100.0,
+                true_percent: num_flops as f32 / NUM_FLOPS as f32 * 100.0,
+            }
+        })
+        .filter(|share| share.dataset_percent < share.true_percent * UNDER_REPRESENTED_RATIO)
+        .collect()
+}
+
+fn get_texture_classes() -> Vec<TextureClass> {
+    let suits = [
+        ("Rainbow", BoardSuit::Rainbow),
+        ("Twotone", BoardSuit::Twotone),
+        ("Monotone", BoardSuit::Montone),
+    ]
+    .map(|(name, suit)| TextureClass {
+        name,
+        matches: Box::new(move |texture| texture.is_suit(&suit)),
+    });
+
+    let pairs = [
+        ("Unpaired", BoardPair::Unpaired),
+        ("Paired", BoardPair::Paired),
+        ("Trips", BoardPair::Trips),
+    ]
+    .map(|(name, pair)| TextureClass {
+        name,
+        matches: Box::new(move |texture| texture.is_pair(&pair)),
+    });
+
+    let heights = [
+        ("3BW", BoardHeight::TripleBW),
+        ("2BW", BoardHeight::DoubleBW),
+        ("1BW", BoardHeight::SingleBW),
+        ("Middling", BoardHeight::Middling),
+        ("Low", BoardHeight::Low),
+    ]
+    .map(|(name, height)| TextureClass {
+        name,
+        matches: Box::new(move |texture| texture.is_height(&height)),
+    });
+
+    suits.into_iter().chain(pairs).chain(heights).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use crate::files::TestDir;
+
+    use super::*;
+
+    #[test]
+    fn test_build_coverage() {
+        let coverage = build_coverage(&Args::for_test(Path::new("./test_data"))).unwrap();
+
+        assert_eq!(coverage.num_canonical_flops, 1_755);
+
+        let sizes: Vec<_> = coverage
+            .sizes
+            .iter()
+            .map(|size| (&size.name[..], size.num_flops, size.missing.len()))
+            .collect();
+        assert_eq!(
+            sizes,
+            vec![("33", 6, 0), ("50", 6, 0), ("75", 6, 0), ("150", 6, 0)]
+        );
+
+        let classes: Vec<_> = coverage
+            .under_represented
+            .iter()
+            .map(|share| share.class)
+            .collect();
+        assert!(classes.contains(&"Monotone"));
+        assert!(!classes.contains(&"Rainbow"));
+    }
+
+    #[test]
+    fn test_build_coverage_missing_boards() {
+        let test_dir = TestDir::new("coverage");
+        let data_dir = test_dir.path();
+        let header = "Tree\tEquity(*)\tEV\tBet 18.15\tCheck\n";
+
+        fs::create_dir_all(data_dir.join("BTN vs BB/33")).unwrap();
+        fs::create_dir_all(data_dir.join("BTN vs BB/75")).unwrap();
+        fs::write(
+            data_dir.join("BTN vs BB/33/after_check.txt"),
+            format!("{header}KsQs2d\t50\t30\t20\t80\n8s8d8c\t50\t30\t20\t80\n"),
+        )
+        .unwrap();
+        fs::write(
+            data_dir.join("BTN vs BB/75/after_check.txt"),
+            format!("{header}KhQh2c\t50\t30\t20\t80\n"),
+        )
+        .unwrap();
+
+        let coverage = build_coverage(&Args::for_test(data_dir)).unwrap();
+
+        assert_eq!(coverage.sizes[0].name, "33");
+        assert!(coverage.sizes[0].missing.is_empty());
+        assert_eq!(coverage.sizes[1].name, "75");
+        assert_eq!(coverage.sizes[1].num_flops, 1);
+        assert_eq!(
+            coverage.sizes[1].missing,
+            vec![Board::try_from("8s8d8c").unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_get_under_represented() {
+        let canonical_flops = enumeration::get_canonical_flops();
+        let monotone = Board::try_from("AsKsTs").unwrap().canonical();
+        let rainbow = Board::try_from("AsKdTc").unwrap().canonical();
+        let dataset_flops = HashSet::from([&monotone, &rainbow]);
+
+        let shares = get_under_represented(&dataset_flops, &canonical_flops);
+        let classes: Vec<_> = shares.iter().map(|share| share.class).collect();
+
+        assert!(classes.contains(&"Twotone"));
+        assert!(!classes.contains(&"Rainbow"));
+        assert!(!classes.contains(&"Monotone"));
+        assert!(!classes.contains(&"3BW"));
+    }
+
+    #[test]
+    fn test_get_under_represented_all_flops() {
+        let canonical_flops = enumeration::get_canonical_flops();
+        let dataset_flops: HashSet<&Board> =
+            canonical_flops.iter().map(|(board, _)| board).collect();
+
+        assert_eq!(
+            get_under_represented(&dataset_flops, &canonical_flops),
+            Vec::new()
+        );
+    }
+}
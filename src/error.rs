@@ -0,0 +1,146 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{calculation::schema::SchemaError, files, poker::ParseError};
+
+#[derive(Debug)]
+pub enum Error {
+    // Invalid command line
+    Args(String),
+    // A directory or file could not be read
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    // The data root does not hold the directories or files asked for
+    Data(String),
+    // A report, or one of its lines, can not be parsed
+    Report {
+        location: Box<Location>,
+        source: SchemaError,
+    },
+    // The parsed reports can not be aggregated
+    Aggregation(String),
+}
+
+// Where in the data root a report error occurred
+#[derive(Debug, PartialEq)]
+pub struct Location {
+    pub spot: String,
+    pub size: String,
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub text: Option<String>,
+}
+
+impl Location {
+    // Reports are stored as <data root>/<spot>/<size>/<action file>
+    pub fn new(path: &Path) -> Location {
+        let size_dir = path.parent();
+        let spot_dir = size_dir.and_then(Path::parent);
+
+        Location {
+            spot: spot_dir.map(files::get_name).unwrap_or_default(),
+            size: size_dir.map(files::get_name).unwrap_or_default(),
+            path: path.to_path_buf(),
+            line: None,
+            text: None,
+        }
+    }
+
+    // Line numbers start at 1 with the header
+    pub fn at_line(&self, line: usize, text: &str) -> Location {
+        Location {
+            spot: self.spot.clone(),
+            size: self.size.clone(),
+            path: self.path.clone(),
+            line: Some(line),
+            text: Some(text.to_string()),
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (spot {}, size {})",
+            self.path.display(),
+            self.spot,
+            self.size
+        )?;
+
+        if let Some(line) = self.line {
+            write!(f, ", line {line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Args(message) => write!(f, "Invalid arguments: {message}"),
+            Error::Io { path, source } => write!(f, "Could not read {}: {source}", path.display()),
+            Error::Data(message) => write!(f, "{message}"),
+            Error::Report { location, source } => {
+                write!(f, "{location}: {source}")?;
+
+                match &location.text {
+                    Some(text) => write!(f, "\n    {}", text.replace('\t', "  ")),
+                    None => Ok(()),
+                }
+            }
+            Error::Aggregation(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Error {
+        Error::Args(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_new() {
+        let location = Location::new(Path::new("./data/BTN vs BB/33/after_check.txt"));
+
+        assert_eq!(location.spot, "BTN vs BB");
+        assert_eq!(location.size, "33");
+        assert_eq!(location.line, None);
+        assert_eq!(
+            location.to_string(),
+            "./data/BTN vs BB/33/after_check.txt (spot BTN vs BB, size 33)"
+        );
+    }
+
+    #[test]
+    fn test_report_error_display() {
+        let location = Location::new(Path::new("./data/BTN vs BB/33/after_check.txt"))
+            .at_line(12, "8s8d8c\t56.5\t-\t69.5\t30.5");
+        let err = Error::Report {
+            location: Box::new(location),
+            source: SchemaError::InvalidCell {
+                column: "EV".to_string(),
+                value: "-".to_string(),
+            },
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "./data/BTN vs BB/33/after_check.txt (spot BTN vs BB, size 33), line 12: \
+            value '-' of column 'EV' is not a number\n    8s8d8c  56.5  -  69.5  30.5"
+        );
+    }
+}
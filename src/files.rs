@@ -1,40 +1,87 @@
 use std::{
-    fs::{self, DirEntry},
+    fs::{self, DirEntry, File},
+    io::BufReader,
     path::Path,
 };
+#[cfg(test)]
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
-pub fn get_files(path: &Path) -> Vec<DirEntry> {
-    get_dir_entries(path)
+use crate::error::Error;
+
+pub fn get_files(path: &Path) -> Result<Vec<DirEntry>, Error> {
+    Ok(get_dir_entries(path)?
         .filter(|entry| entry.path().is_file())
-        .collect()
+        .collect())
 }
 
-pub fn get_dirs(path: &Path) -> Vec<DirEntry> {
-    get_dir_entries(path)
+pub fn get_dirs(path: &Path) -> Result<Vec<DirEntry>, Error> {
+    Ok(get_dir_entries(path)?
         .filter(|entry| entry.path().is_dir())
-        .collect()
+        .collect())
 }
 
-fn get_dir_entries(path: &Path) -> impl Iterator<Item = DirEntry> {
-    let name = get_name(path);
+fn get_dir_entries(path: &Path) -> Result<impl Iterator<Item = DirEntry>, Error> {
+    let entries = fs::read_dir(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
 
-    fs::read_dir(path)
-        .unwrap_or_else(|_| panic!("Error reading directory: {name}"))
+    Ok(entries
         .filter_map(Result::ok)
-        .filter(|entry| !is_hidden(entry))
+        .filter(|entry| !is_hidden(entry)))
 }
 
 pub fn get_name(path: &Path) -> String {
     path.file_stem()
-        .and_then(|x| x.to_str())
-        .map(|x| x.to_string())
-        .expect("Could not extract path name")
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+pub fn open_file(path: &Path) -> Result<BufReader<File>, Error> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })
 }
 
 fn is_hidden(entry: &DirEntry) -> bool {
     get_name(&entry.path()).starts_with(".")
 }
 
+// A fixture directory of its own for each test, removed with everything in it when dropped
+#[cfg(test)]
+pub struct TestDir(PathBuf);
+
+#[cfg(test)]
+impl TestDir {
+    pub fn new(name: &str) -> TestDir {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let path =
+            std::env::temp_dir().join(format!("gto-parse-test-{name}-{}-{id}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TestDir(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,6 +91,7 @@ mod tests {
         let test_dir = Path::new("./test_data");
 
         let mut file_contents: Vec<String> = get_files(test_dir)
+            .unwrap()
             .iter()
             .map(|file| fs::read_to_string(file.path()).unwrap())
             .collect();
@@ -63,6 +111,7 @@ mod tests {
         let test_dir = Path::new("./test_data");
 
         let mut dir_names: Vec<String> = get_dirs(test_dir)
+            .unwrap()
             .iter()
             .map(|dir| get_name(&dir.path()))
             .collect();
@@ -81,11 +130,12 @@ mod tests {
         let test_dir = Path::new("./test_data/BTN vs BB");
 
         let mut file_contents: Vec<_> = get_dirs(test_dir)
+            .unwrap()
             .iter()
             .map(|dir| {
                 let dir_path = &dir.path();
 
-                let files = get_files(dir_path);
+                let files = get_files(dir_path).unwrap();
                 assert_eq!(files.len(), 1);
                 let file = &files[0];
 
@@ -150,6 +200,7 @@ Ts6s4d	70.407	10.865	3.348	77.653
         let test_dir = Path::new("./test_data");
 
         let mut entry_names: Vec<String> = get_dir_entries(test_dir)
+            .unwrap()
             .map(|dir| get_name(&dir.path()))
             .collect();
 
@@ -171,9 +222,8 @@ Ts6s4d	70.407	10.865	3.348	77.653
     }
 
     #[test]
-    #[should_panic]
     fn test_get_dir_entries_invalid() {
-        let _: Vec<_> = get_dir_entries(Path::new("./test_datas")).collect();
+        assert!(get_dir_entries(Path::new("./test_datas")).is_err());
     }
 
     #[test]
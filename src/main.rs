@@ -1,13 +1,49 @@
 mod args;
+mod cache;
 mod calculation;
+mod catalog;
+mod config;
+mod coverage;
+mod error;
 mod files;
 mod output;
 mod poker;
 
+use std::process;
+
+use args::Command;
+use cache::CacheAction;
+use error::Error;
+
 fn main() {
-    let args = args::read_cmdline_args();
+    if let Err(err) = run() {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
 
-    let (data_rows, boards) = calculation::build_data_rows_with_boards(args);
+fn run() -> Result<(), Error> {
+    match args::read_cmdline_args()? {
+        Command::Aggregate(args) => {
+            let aggregation = calculation::build_aggregation(args)?;
+            output::print_result(aggregation);
+        }
+        Command::List(data_dir) => {
+            let catalog = catalog::build_catalog(&data_dir)?;
+            output::print_catalog(&catalog);
+        }
+        Command::Coverage(args) => {
+            let coverage = coverage::build_coverage(&args)?;
+            output::print_coverage(&coverage);
+        }
+        Command::Cache(action, data_dir) => {
+            let summary = match action {
+                CacheAction::Clear => cache::clear(&data_dir)?,
+                CacheAction::Rebuild => cache::rebuild(&data_dir)?,
+            };
+            output::print_cache_summary(&summary);
+        }
+    }
 
-    output::print_result(data_rows, boards);
+    Ok(())
 }
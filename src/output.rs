@@ -1,10 +1,97 @@
-use prettytable::{format, row, Row, Table};
+use prettytable::{format, Cell, Row, Table};
 
-use crate::{calculation::datarow::DataRow, poker::board::Board};
+use crate::{
+    cache::CacheSummary,
+    calculation::{
+        datarow::DataRow,
+        sizing::{self, BetSizing},
+        Aggregation,
+    },
+    catalog::Catalog,
+    coverage::Coverage,
+    poker::{action::Action, board::Board},
+};
 
-pub fn print_result(data_rows: Vec<DataRow>, boards: Vec<Board>) {
-    print_considered_board(boards);
-    print_table(data_rows);
+pub fn print_result(aggregation: Aggregation) {
+    print_node(&aggregation.actions);
+    print_considered_board(aggregation.boards);
+    print_sizings(aggregation.pot, &aggregation.sizings);
+    print_table(
+        aggregation.data_rows,
+        &aggregation.actions,
+        &aggregation.sizings,
+    );
+    print_warnings(&aggregation.warnings);
+}
+
+pub fn print_catalog(catalog: &Catalog) {
+    for spot in &catalog.spots {
+        println!("{}", spot.name);
+
+        for size in &spot.sizes {
+            println!("  {}: {}", size.name, size.action_files.join(", "));
+        }
+    }
+
+    print_warnings(&catalog.warnings);
+}
+
+pub fn print_coverage(coverage: &Coverage) {
+    for size in &coverage.sizes {
+        println!(
+            "{}: {} of {} canonical flops",
+            size.name, size.num_flops, coverage.num_canonical_flops
+        );
+
+        if !size.missing.is_empty() {
+            let joined_boards = size
+                .missing
+                .iter()
+                .map(|board| board.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            println!("  Missing boards of other sizes: {joined_boards}");
+        }
+    }
+
+    for share in &coverage.under_represented {
+        println!(
+            "Under-represented: {} with {:.1}% of the boards, but {:.1}% of all flops",
+            share.class, share.dataset_percent, share.true_percent
+        );
+    }
+
+    print_warnings(&coverage.warnings);
+}
+
+pub fn print_cache_summary(summary: &CacheSummary) {
+    if summary.num_removed > 0 {
+        println!(
+            "Removed {} cached reports from {}",
+            summary.num_removed,
+            summary.cache_dir.display()
+        );
+    }
+    if summary.num_cached > 0 {
+        println!(
+            "Cached {} reports in {}",
+            summary.num_cached,
+            summary.cache_dir.display()
+        );
+    }
+
+    print_warnings(&summary.warnings);
+}
+
+fn print_node(actions: &[Action]) {
+    let player = if Action::is_oop_to_act(actions) {
+        "OOP"
+    } else {
+        "IP"
+    };
+
+    println!("Node: {} ({player} to act)", Action::format_path(actions));
 }
 
 fn print_considered_board(boards: Vec<Board>) {
@@ -17,16 +104,40 @@ fn print_considered_board(boards: Vec<Board>) {
     println!("Considered boards: {}", joined_boards);
 }
 
-fn print_table(data_rows: Vec<DataRow>) {
+fn print_sizings(pot: Option<f32>, sizings: &[BetSizing]) {
+    let Some(pot) = pot else {
+        return;
+    };
+
+    let joined_sizings = sizings
+        .iter()
+        .map(|sizing| match sizing.percent {
+            Some(percent) => format!("{} = {} ({percent:.1}%)", sizing.betsize, sizing.chips),
+            None => format!("{} = {}", sizing.betsize, sizing.chips),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    println!("Pot: {pot:.2}, bets: {joined_sizings}");
+}
+
+fn print_warnings(warnings: &[String]) {
+    for warning in warnings {
+        println!("Warning: {warning}");
+    }
+}
+
+fn print_table(data_rows: Vec<DataRow>, actions: &[Action], sizings: &[BetSizing]) {
     let max_row = get_max_ev_row(&data_rows);
+    let freq_columns = get_freq_columns(&data_rows);
 
     let mut table = Table::new();
     table.set_format(*format::consts::FORMAT_BOX_CHARS);
-    table.set_titles(row!["Size", "EQ", "EV", "Bet", "Check", "EV Difference"]);
+    table.set_titles(build_title_row(&freq_columns, actions));
 
     data_rows
         .iter()
-        .map(|row| build_table_row(row, max_row))
+        .map(|row| build_table_row(row, max_row, &freq_columns, actions, sizings))
         .for_each(|row| {
             table.add_row(row);
         });
@@ -34,6 +145,86 @@ fn print_table(data_rows: Vec<DataRow>) {
     table.printstd();
 }
 
+// The n-th column of an action, e.g. the second of three bet sizes
+#[derive(Debug, PartialEq)]
+struct FreqColumn {
+    action: Action,
+    ordinal: usize,
+}
+
+fn get_freq_columns(data_rows: &[DataRow]) -> Vec<FreqColumn> {
+    let mut columns: Vec<FreqColumn> = Vec::new();
+
+    for row in data_rows {
+        for (i, freq) in row.freqs.iter().enumerate() {
+            let ordinal = row.freqs[..i]
+                .iter()
+                .filter(|prev| prev.action == freq.action)
+                .count();
+
+            let column = FreqColumn {
+                action: freq.action.clone(),
+                ordinal,
+            };
+
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+    }
+
+    columns
+}
+
+// Only worth a column if bets are split over several columns
+fn shows_any_bet(freq_columns: &[FreqColumn]) -> bool {
+    freq_columns
+        .iter()
+        .filter(|column| is_aggressive(&column.action))
+        .count()
+        > 1
+}
+
+fn is_aggressive(action: &Action) -> bool {
+    matches!(action, Action::Bet | Action::Raise)
+}
+
+fn build_title_row(freq_columns: &[FreqColumn], actions: &[Action]) -> Row {
+    let mut titles = vec!["Size".to_string(), "EQ".to_string(), "EV".to_string()];
+
+    titles.extend(freq_columns.iter().map(|column| {
+        let mut title = match column.action {
+            // A bet out of position at the root leads into the preflop aggressor
+            Action::Bet if actions.is_empty() => String::from("lead"),
+            _ => column.action.to_long_string(),
+        };
+        title[..1].make_ascii_uppercase();
+
+        let has_several = freq_columns
+            .iter()
+            .any(|other| other.action == column.action && other.ordinal > 0);
+
+        if has_several {
+            format!("{title} {}", column.ordinal + 1)
+        } else {
+            title
+        }
+    }));
+
+    if shows_any_bet(freq_columns) {
+        titles.push("Any Bet".to_string());
+    }
+
+    if Action::faces_bet(actions) {
+        titles.push("MDF".to_string());
+        titles.push("Overfold".to_string());
+    }
+
+    titles.push("EV Difference".to_string());
+
+    Row::new(titles.iter().map(|title| Cell::new(title)).collect())
+}
+
 fn get_max_ev_row(data_rows: &[DataRow]) -> &DataRow {
     data_rows
         .iter()
@@ -45,32 +236,91 @@ fn get_max_ev_row(data_rows: &[DataRow]) -> &DataRow {
         .expect("could not determine size of max row")
 }
 
-fn build_table_row(row: &DataRow, max_row: &DataRow) -> Row {
+fn build_table_row(
+    row: &DataRow,
+    max_row: &DataRow,
+    freq_columns: &[FreqColumn],
+    actions: &[Action],
+    sizings: &[BetSizing],
+) -> Row {
+    let is_max_row = row == max_row;
+    let style = |style_spec: &'static str| if is_max_row { style_spec } else { "" };
+
     let size_str = row
         .size
         .as_ref()
         .map(|size| size.to_string())
         .unwrap_or(String::from("-"));
 
-    let eq_str = format!("{:.2}", row.eq);
-    let ev_str = format!("{:.2}", row.ev);
-    let bet_str = format!("{:.2}", row.bet_freq);
-    let check_str = format!("{:.2}", row.check_freq);
+    let mut cells = vec![
+        Cell::new(&size_str).style_spec(style("b")),
+        Cell::new(&format!("{:.2}", row.eq)).style_spec(style("b")),
+        Cell::new(&format!("{:.2}", row.ev)).style_spec(style("b")),
+    ];
+
+    cells.extend(freq_columns.iter().map(|column| {
+        let freq_str = row
+            .freqs
+            .iter()
+            .filter(|freq| freq.action == column.action)
+            .nth(column.ordinal)
+            .map(|freq| format!("{:.2}", freq.freq))
+            .unwrap_or(String::from("-"));
+
+        let style_spec = match column.action {
+            Action::Bet | Action::Raise => "bFR",
+            Action::Check | Action::Call => "bFG",
+            Action::Fold => "bFB",
+        };
+
+        Cell::new(&freq_str).style_spec(style(style_spec))
+    }));
+
+    if shows_any_bet(freq_columns) {
+        cells.push(Cell::new(&format!("{:.2}", row.any_bet_freq())).style_spec(style("bFR")));
+    }
+
+    if Action::faces_bet(actions) {
+        // Folding more than 100 - MDF percent lets any two cards bluff profitably.
+        // Sizes are labeled by the first bet, so the price of a raise is unknown.
+        let mdf = row
+            .size
+            .as_ref()
+            .filter(|_| actions.last() != Some(&Action::Raise))
+            .and_then(|size| sizing::mdf(size, sizings));
+        let overfold = mdf.map(|mdf| row.freq(&Action::Fold) - (100.0 - mdf));
+
+        let mdf_str = mdf
+            .map(|mdf| format!("{mdf:.2}"))
+            .unwrap_or(String::from("-"));
+        let overfold_str = overfold
+            .map(|overfold| format!("{overfold:+.2}"))
+            .unwrap_or(String::from("-"));
+
+        cells.push(Cell::new(&mdf_str).style_spec(style("b")));
+        cells.push(Cell::new(&overfold_str).style_spec(style("bFB")));
+    }
 
     let ev_diff = row.ev - max_row.ev;
     let bb_per_100 = ev_diff * 10.0;
-    let ev_diff_str = format!("{ev_diff:.2} = {bb_per_100:.1} BB/100");
 
-    if row == max_row {
-        row![b->size_str, b->eq_str, b->ev_str, bFR->bet_str, bFG->check_str, b->ev_diff]
+    if is_max_row {
+        cells.push(Cell::new(&ev_diff.to_string()).style_spec("b"));
     } else {
-        row![size_str, eq_str, ev_str, bet_str, check_str, Fr->ev_diff_str]
+        cells.push(Cell::new(&format!("{ev_diff:.2} = {bb_per_100:.1} BB/100")).style_spec("Fr"));
     }
+
+    Row::new(cells)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::poker::betsize::Betsize;
+    use prettytable::row;
+
+    use crate::{
+        calculation::datarow::{bet_check_freqs, ActionFreq},
+        poker::betsize::Betsize,
+    };
 
     use super::*;
 
@@ -78,84 +328,83 @@ mod tests {
     fn test_get_max_ev_row() {
         let data_rows = vec![
             DataRow {
-                size: Some(Betsize::Size33),
+                size: Some(Betsize::Percent(33)),
                 eq: 60.755173,
                 ev: 28.557,
-                bet_freq: 28.126001,
-                check_freq: 65.877335,
+                freqs: bet_check_freqs(18.15, 28.126001, 65.877335),
             },
             DataRow {
-                size: Some(Betsize::Size50),
+                size: Some(Betsize::Percent(50)),
                 eq: 52.90833,
                 ev: 41.9065,
-                bet_freq: 28.626001,
-                check_freq: 75.374504,
+                freqs: bet_check_freqs(27.5, 28.626001, 75.374504),
             },
             DataRow {
-                size: Some(Betsize::Size75),
+                size: Some(Betsize::Percent(75)),
                 eq: 61.075,
                 ev: 39.07317,
-                bet_freq: 21.626001,
-                check_freq: 69.04117,
+                freqs: bet_check_freqs(41.25, 21.626001, 69.04117),
             },
             DataRow {
-                size: Some(Betsize::Size150),
+                size: Some(Betsize::Percent(150)),
                 eq: 55.74167,
                 ev: 31.739836,
-                bet_freq: 27.459335,
-                check_freq: 71.70783,
+                freqs: bet_check_freqs(82.5, 27.459335, 71.70783),
             },
         ];
 
         let max_row = get_max_ev_row(&data_rows);
 
-        assert_eq!(*max_row.size.as_ref().unwrap(), Betsize::Size50);
+        assert_eq!(*max_row.size.as_ref().unwrap(), Betsize::Percent(50));
     }
 
     #[test]
     fn test_build_table_row() {
         let data_rows = [
             DataRow {
-                size: Some(Betsize::Size33),
+                size: Some(Betsize::Percent(33)),
                 eq: 60.755173,
                 ev: 28.557,
-                bet_freq: 28.126001,
-                check_freq: 65.877335,
+                freqs: bet_check_freqs(18.15, 28.126001, 65.877335),
             },
             DataRow {
-                size: Some(Betsize::Size50),
+                size: Some(Betsize::Percent(50)),
                 eq: 52.90833,
                 ev: 41.9065,
-                bet_freq: 28.626001,
-                check_freq: 75.374504,
+                freqs: bet_check_freqs(27.5, 28.626001, 75.374504),
             },
             DataRow {
-                size: Some(Betsize::Size75),
+                size: Some(Betsize::Percent(75)),
                 eq: 61.075,
                 ev: 39.07317,
-                bet_freq: 21.626001,
-                check_freq: 69.04117,
+                freqs: bet_check_freqs(41.25, 21.626001, 69.04117),
             },
             DataRow {
-                size: Some(Betsize::Size150),
+                size: Some(Betsize::Percent(150)),
                 eq: 55.74167,
                 ev: 31.739836,
-                bet_freq: 27.459335,
-                check_freq: 71.70783,
+                freqs: bet_check_freqs(82.5, 27.459335, 71.70783),
             },
         ];
 
         let max_row = DataRow {
-            size: Some(Betsize::Size50),
+            size: Some(Betsize::Percent(50)),
             eq: 52.90833,
             ev: 41.9065,
-            bet_freq: 28.626001,
-            check_freq: 75.374504,
+            freqs: bet_check_freqs(27.5, 28.626001, 75.374504),
         };
 
         let table_rows: Vec<_> = data_rows
             .iter()
-            .map(|row| build_table_row(row, &max_row))
+            .map(|row| {
+                build_table_row(
+                    row,
+                    &max_row,
+                    &get_freq_columns(&data_rows),
+                    &[Action::Check],
+                    &[],
+                )
+            })
             .collect();
 
         let expected_table_row_33 =
@@ -173,4 +422,197 @@ mod tests {
         assert!(table_rows.contains(&expected_table_row_75));
         assert!(table_rows.contains(&expected_table_row_150));
     }
+
+    #[test]
+    fn test_several_bets_table() {
+        let freqs = |bet1: f32, bet2: f32, check: f32| {
+            vec![
+                ActionFreq {
+                    action: Action::Bet,
+                    chips: Some(18.15),
+                    freq: bet1,
+                },
+                ActionFreq {
+                    action: Action::Bet,
+                    chips: Some(82.5),
+                    freq: bet2,
+                },
+                ActionFreq {
+                    action: Action::Check,
+                    chips: None,
+                    freq: check,
+                },
+            ]
+        };
+
+        let data_rows = [
+            DataRow {
+                size: Some(Betsize::Percent(33)),
+                eq: 60.755173,
+                ev: 28.557,
+                freqs: freqs(20.0, 10.0, 70.0),
+            },
+            DataRow {
+                size: Some(Betsize::Percent(50)),
+                eq: 52.90833,
+                ev: 41.9065,
+                freqs: freqs(5.0, 15.5, 79.5),
+            },
+        ];
+
+        let freq_columns = get_freq_columns(&data_rows);
+        assert_eq!(
+            freq_columns,
+            vec![
+                FreqColumn {
+                    action: Action::Bet,
+                    ordinal: 0
+                },
+                FreqColumn {
+                    action: Action::Bet,
+                    ordinal: 1
+                },
+                FreqColumn {
+                    action: Action::Check,
+                    ordinal: 0
+                },
+            ]
+        );
+
+        assert_eq!(
+            build_title_row(&freq_columns, &[Action::Check]),
+            row![
+                "Size",
+                "EQ",
+                "EV",
+                "Bet 1",
+                "Bet 2",
+                "Check",
+                "Any Bet",
+                "EV Difference"
+            ]
+        );
+
+        assert_eq!(
+            build_table_row(
+                &data_rows[0],
+                &data_rows[1],
+                &freq_columns,
+                &[Action::Check],
+                &[]
+            ),
+            row!["33", "60.76", "28.56", "20.00", "10.00", "70.00", "30.00", Fr->"-13.35 = -133.5 BB/100"]
+        );
+        assert_eq!(
+            build_table_row(
+                &data_rows[1],
+                &data_rows[1],
+                &freq_columns,
+                &[Action::Check],
+                &[]
+            ),
+            row![b->"50", b->"52.91", b->"41.91", bFR->"5.00", bFR->"15.50", bFG->"79.50", bFR->"20.50", b->"0"]
+        );
+    }
+
+    #[test]
+    fn test_facing_bet_table() {
+        let data_rows = [DataRow {
+            size: Some(Betsize::Percent(50)),
+            eq: 40.0,
+            ev: 10.0,
+            freqs: vec![
+                ActionFreq {
+                    action: Action::Raise,
+                    chips: Some(60.0),
+                    freq: 10.0,
+                },
+                ActionFreq {
+                    action: Action::Call,
+                    chips: None,
+                    freq: 50.0,
+                },
+                ActionFreq {
+                    action: Action::Fold,
+                    chips: None,
+                    freq: 40.0,
+                },
+            ],
+        }];
+
+        let freq_columns = get_freq_columns(&data_rows);
+
+        assert_eq!(
+            build_title_row(&freq_columns, &[Action::Check, Action::Bet]),
+            row![
+                "Size",
+                "EQ",
+                "EV",
+                "Raise",
+                "Call",
+                "Fold",
+                "MDF",
+                "Overfold",
+                "EV Difference"
+            ]
+        );
+        assert_eq!(
+            build_table_row(
+                &data_rows[0],
+                &data_rows[0],
+                &freq_columns,
+                &[Action::Check, Action::Bet],
+                &[]
+            ),
+            row![b->"50", b->"40.00", b->"10.00", bFR->"10.00", bFG->"50.00", bFB->"40.00", b->"66.67", bFB->"+6.67", b->"0"]
+        );
+
+        // A pot sized bet in the folder labeled 50
+        let sizings = [BetSizing {
+            betsize: Betsize::Percent(50),
+            chips: 55.0,
+            percent: Some(100.0),
+        }];
+        assert_eq!(
+            build_table_row(
+                &data_rows[0],
+                &data_rows[0],
+                &freq_columns,
+                &[Action::Check, Action::Bet],
+                &sizings
+            ),
+            row![b->"50", b->"40.00", b->"10.00", bFR->"10.00", bFG->"50.00", bFB->"40.00", b->"50.00", bFB->"-10.00", b->"0"]
+        );
+
+        let facing_raise = [Action::Check, Action::Bet, Action::Raise];
+        assert_eq!(
+            build_title_row(&freq_columns, &facing_raise),
+            build_title_row(&freq_columns, &[Action::Check, Action::Bet])
+        );
+        assert_eq!(
+            build_table_row(
+                &data_rows[0],
+                &data_rows[0],
+                &freq_columns,
+                &facing_raise,
+                &sizings
+            ),
+            row![b->"50", b->"40.00", b->"10.00", bFR->"10.00", bFG->"50.00", bFB->"40.00", b->"-", bFB->"-", b->"0"]
+        );
+    }
+
+    #[test]
+    fn test_root_titles() {
+        let data_rows = [DataRow {
+            size: Some(Betsize::Percent(33)),
+            eq: 45.0,
+            ev: 24.0,
+            freqs: bet_check_freqs(18.15, 20.0, 80.0),
+        }];
+
+        assert_eq!(
+            build_title_row(&get_freq_columns(&data_rows), &[]),
+            row!["Size", "EQ", "EV", "Lead", "Check", "EV Difference"]
+        );
+    }
 }
@@ -10,6 +10,8 @@ const BET_STR: &str = "B";
 const CALL_STR: &str = "C";
 const RAISE_STR: &str = "R";
 const FOLD_STR: &str = "F";
+const PATH_SEPARATOR: char = '-';
+const ROOT_STR: &str = "ROOT";
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Action {
@@ -50,6 +52,49 @@ impl Display for Action {
 }
 
 impl Action {
+    pub fn from_long_str(s: &str) -> Result<Action, ParseError> {
+        match s {
+            "check" => Ok(Action::Check),
+            "bet" => Ok(Action::Bet),
+            "call" => Ok(Action::Call),
+            "raise" => Ok(Action::Raise),
+            "fold" => Ok(Action::Fold),
+            _ => Err(ParseError::str("action", s)),
+        }
+    }
+
+    // Sequence of actions leading to a node, e.g. "X-B-R", or "ROOT" for the first decision
+    pub fn parse_path(s: &str) -> Result<Vec<Action>, ParseError> {
+        if s.eq_ignore_ascii_case(ROOT_STR) {
+            return Ok(Vec::new());
+        }
+
+        s.split(PATH_SEPARATOR)
+            .map(|action| Action::from_str(&action.to_uppercase()))
+            .collect()
+    }
+
+    pub fn format_path(path: &[Action]) -> String {
+        if path.is_empty() {
+            return ROOT_STR.to_lowercase();
+        }
+
+        path.iter()
+            .map(|action| action.to_string())
+            .collect::<Vec<_>>()
+            .join(&PATH_SEPARATOR.to_string())
+    }
+
+    // A raise is a bet too, the player facing it can call or fold
+    pub fn faces_bet(path: &[Action]) -> bool {
+        matches!(path.last(), Some(Action::Bet | Action::Raise))
+    }
+
+    // OOP acts first, then the players alternate
+    pub fn is_oop_to_act(path: &[Action]) -> bool {
+        path.len().is_multiple_of(2)
+    }
+
     pub fn to_long_string(&self) -> String {
         let s = match self {
             Action::Check => "check",
@@ -62,3 +107,49 @@ impl Action {
         s.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(Action::parse_path("X").unwrap(), vec![Action::Check]);
+        assert_eq!(
+            Action::parse_path("x-b-R").unwrap(),
+            vec![Action::Check, Action::Bet, Action::Raise]
+        );
+        assert_eq!(Action::parse_path("root").unwrap(), Vec::new());
+
+        assert!(Action::parse_path("").is_err());
+        assert!(Action::parse_path("X-").is_err());
+        assert!(Action::parse_path("X-Y").is_err());
+    }
+
+    #[test]
+    fn test_format_path() {
+        assert_eq!(Action::format_path(&[]), "root");
+        assert_eq!(
+            Action::format_path(&[Action::Check, Action::Bet, Action::Raise]),
+            "X-B-R"
+        );
+    }
+
+    #[test]
+    fn test_path_node() {
+        assert!(Action::is_oop_to_act(&[]));
+        assert!(!Action::is_oop_to_act(&[Action::Check]));
+        assert!(Action::is_oop_to_act(&[Action::Check, Action::Bet]));
+
+        assert!(Action::faces_bet(&[Action::Check, Action::Bet]));
+        assert!(Action::faces_bet(&[
+            Action::Check,
+            Action::Bet,
+            Action::Raise
+        ]));
+        assert!(Action::faces_bet(&[Action::Bet, Action::Raise]));
+        assert!(!Action::faces_bet(&[Action::Check]));
+        assert!(!Action::faces_bet(&[Action::Bet, Action::Call]));
+        assert!(!Action::faces_bet(&[]));
+    }
+}
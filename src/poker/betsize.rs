@@ -5,28 +5,26 @@ use std::{
 
 use super::ParseError;
 
-const SIZE33_STR: &str = "33";
-const SIZE50_STR: &str = "50";
-const SIZE75_STR: &str = "75";
-const SIZE150_STR: &str = "150";
+const ALL_IN_STR: &str = "AI";
+const ALL_IN_ALIASES: [&str; 4] = [ALL_IN_STR, "ALLIN", "ALL-IN", "ALL_IN"];
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+// Declared so that all-in sorts after every percentage
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Betsize {
-    Size33,
-    Size50,
-    Size75,
-    Size150,
+    Percent(u32),
+    AllIn,
 }
 
 impl FromStr for Betsize {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Betsize, ParseError> {
-        match s {
-            SIZE33_STR => Ok(Betsize::Size33),
-            SIZE50_STR => Ok(Betsize::Size50),
-            SIZE75_STR => Ok(Betsize::Size75),
-            SIZE150_STR => Ok(Betsize::Size150),
+        if ALL_IN_ALIASES.contains(&&s.to_uppercase()[..]) {
+            return Ok(Betsize::AllIn);
+        }
+
+        match s.parse::<u32>() {
+            Ok(percent) if percent > 0 => Ok(Betsize::Percent(percent)),
             _ => Err(ParseError::str("betsize", s)),
         }
     }
@@ -34,13 +32,78 @@ impl FromStr for Betsize {
 
 impl Display for Betsize {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let s = match self {
-            Betsize::Size33 => SIZE33_STR,
-            Betsize::Size50 => SIZE50_STR,
-            Betsize::Size75 => SIZE75_STR,
-            Betsize::Size150 => SIZE150_STR,
-        };
-
-        write!(f, "{}", s)
+        match self {
+            Betsize::Percent(percent) => write!(f, "{}", percent),
+            Betsize::AllIn => write!(f, "{}", ALL_IN_STR),
+        }
+    }
+}
+
+impl Betsize {
+    pub fn percent(&self) -> Option<u32> {
+        match self {
+            Betsize::Percent(percent) => Some(*percent),
+            Betsize::AllIn => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_betsize_from_str() {
+        assert_eq!(Betsize::from_str("10").unwrap(), Betsize::Percent(10));
+        assert_eq!(Betsize::from_str("33").unwrap(), Betsize::Percent(33));
+        assert_eq!(Betsize::from_str("66").unwrap(), Betsize::Percent(66));
+        assert_eq!(Betsize::from_str("125").unwrap(), Betsize::Percent(125));
+        assert_eq!(Betsize::from_str("200").unwrap(), Betsize::Percent(200));
+        assert_eq!(Betsize::from_str("AI").unwrap(), Betsize::AllIn);
+        assert_eq!(Betsize::from_str("allin").unwrap(), Betsize::AllIn);
+        assert_eq!(Betsize::from_str("All-In").unwrap(), Betsize::AllIn);
+    }
+
+    #[test]
+    fn test_betsize_from_str_invalid() {
+        assert!(Betsize::from_str("0").is_err());
+        assert!(Betsize::from_str("-33").is_err());
+        assert!(Betsize::from_str("33.5").is_err());
+        assert!(Betsize::from_str("33%").is_err());
+        assert!(Betsize::from_str("").is_err());
+        assert!(Betsize::from_str("max").is_err());
+    }
+
+    #[test]
+    fn test_betsize_display() {
+        assert_eq!(Betsize::Percent(75).to_string(), "75");
+        assert_eq!(Betsize::AllIn.to_string(), "AI");
+    }
+
+    #[test]
+    fn test_betsize_ord() {
+        let mut betsizes = vec![
+            Betsize::AllIn,
+            Betsize::Percent(150),
+            Betsize::Percent(10),
+            Betsize::Percent(33),
+        ];
+        betsizes.sort();
+
+        assert_eq!(
+            betsizes,
+            vec![
+                Betsize::Percent(10),
+                Betsize::Percent(33),
+                Betsize::Percent(150),
+                Betsize::AllIn,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_betsize_percent() {
+        assert_eq!(Betsize::Percent(50).percent(), Some(50));
+        assert_eq!(Betsize::AllIn.percent(), None);
     }
 }
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+
+use crate::poker::{card::Card, suit::Suit};
+
+use super::{Board, SPADES};
+
+/*
+ * Suits are interchangeable before any hole cards are known, so boards that only
+ * differ by a permutation of suits (KsQs2d, KhQh2c) are strategically the same.
+ * The canonical form is the smallest of all 24 suit permutations of a board,
+ * two boards are isomorphic exactly if their canonical forms are equal.
+ */
+impl Board {
+    pub fn canonical(&self) -> Board {
+        get_suit_permutations()
+            .iter()
+            .map(|permutation| self.permute_suits(permutation))
+            .min_by(|board1, board2| board1.sort_key().cmp(&board2.sort_key()))
+            .expect("There is always a suit permutation")
+    }
+
+    // Number of distinct boards this one stands for, e.g. 24 for a rainbow flop
+    pub fn num_isomorphic_variants(&self) -> usize {
+        get_suit_permutations()
+            .iter()
+            .map(|permutation| self.permute_suits(permutation))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    // The permutation maps each suit of Suit::ALL to the suit at the same index
+    fn permute_suits(&self, permutation: &[Suit; 4]) -> Board {
+        let permute = |card: Card| Card::new(card.rank(), permutation[card.suit() as usize]);
+        let mask = Suit::ALL
+            .into_iter()
+            .zip(permutation)
+            .fold(0, |mask, (from, to)| {
+                mask | (self.mask >> from as u32 & SPADES) << *to as u32
+            });
+
+        Board {
+            mask,
+            turn: self.turn.map(permute),
+            river: self.river.map(permute),
+        }
+    }
+
+    /*
+     * Comparing the masks compares the cards from the highest down,
+     * boards of the same size are ordered like their Display strings read
+     */
+    fn sort_key(&self) -> (u64, Option<Card>, Option<Card>) {
+        (self.mask, self.turn, self.river)
+    }
+}
+
+// Only tests compare two boards, the aggregation joins them through maps keyed by canonical form
+#[cfg(test)]
+impl Board {
+    pub fn is_isomorphic(&self, other: &Board) -> bool {
+        self.canonical() == other.canonical()
+    }
+}
+
+fn get_suit_permutations() -> Vec<[Suit; 4]> {
+    let mut permutations = Vec::new();
+
+    for first in Suit::ALL {
+        for second in Suit::ALL.into_iter().filter(|suit| *suit != first) {
+            for third in Suit::ALL
+                .into_iter()
+                .filter(|suit| *suit != first && *suit != second)
+            {
+                let fourth = Suit::ALL
+                    .into_iter()
+                    .find(|suit| ![first, second, third].contains(suit))
+                    .expect("Four suits leave one for the last position");
+
+                permutations.push([first, second, third, fourth]);
+            }
+        }
+    }
+
+    permutations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(s: &str) -> Board {
+        Board::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn test_suit_permutations() {
+        let permutations = get_suit_permutations();
+        assert_eq!(permutations.len(), 24);
+        assert!(permutations.contains(&Suit::ALL));
+        assert!(permutations
+            .iter()
+            .all(|permutation| Suit::ALL.iter().all(|suit| permutation.contains(suit))));
+    }
+
+    #[test]
+    fn test_canonical() {
+        assert_eq!(board("KsQs2d").canonical(), board("KhQh2c").canonical());
+        assert_eq!(board("KsQs2d").canonical(), board("2cKdQd").canonical());
+        assert_ne!(board("KsQs2d").canonical(), board("KsQd2s").canonical());
+        assert_ne!(board("KsQs2s").canonical(), board("KsQs2d").canonical());
+
+        let canonical = board("8h8d6d").canonical();
+        assert_eq!(canonical.canonical(), canonical);
+        assert_eq!(canonical.num_cards(), 3);
+    }
+
+    #[test]
+    fn test_is_isomorphic() {
+        assert!(board("KsQs2d").is_isomorphic(&board("KhQh2c")));
+        assert!(board("AsKdTc").is_isomorphic(&board("AhKsTd")));
+        assert!(!board("AsKdTc").is_isomorphic(&board("AsKsTc")));
+        assert!(!board("AsKdTc").is_isomorphic(&board("AsKdTc2h")));
+    }
+
+    #[test]
+    fn test_num_isomorphic_variants() {
+        assert_eq!(board("AsKdTc").num_isomorphic_variants(), 24);
+        assert_eq!(board("AsKsTc").num_isomorphic_variants(), 12);
+        assert_eq!(board("AsKsTs").num_isomorphic_variants(), 4);
+        assert_eq!(board("AsAdTc").num_isomorphic_variants(), 12);
+        assert_eq!(board("AsAdTs").num_isomorphic_variants(), 12);
+        assert_eq!(board("AsAdAc").num_isomorphic_variants(), 4);
+        assert_eq!(board("AsKsTs2s").num_isomorphic_variants(), 4);
+    }
+
+    #[test]
+    fn test_is_isomorphic_keeps_turn() {
+        assert!(board("KsQs2dAh").is_isomorphic(&board("KhQh2cAs")));
+        assert!(!board("KsQs2dAh").is_isomorphic(&board("KsAhQs2d")));
+    }
+}
@@ -1,8 +1,8 @@
-use crate::poker::{card::Card, rank::RankHeight, ParseError};
+use crate::poker::{rank::RankHeight, ParseError};
 
-use super::Board;
+use super::{connectivity::Connectivity, Board, STRAIGHTS};
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum Connection {
     Disconnected,
     Gutshot,
@@ -10,6 +10,21 @@ pub enum Connection {
     Wheel,
     NormalStraight,
     AnyStraight,
+    OneCardStraight,
+    BoardStraight,
+}
+
+impl Connection {
+    pub const ALL: [Connection; 8] = [
+        Connection::Disconnected,
+        Connection::Gutshot,
+        Connection::Oesd,
+        Connection::Wheel,
+        Connection::NormalStraight,
+        Connection::AnyStraight,
+        Connection::OneCardStraight,
+        Connection::BoardStraight,
+    ];
 }
 
 impl TryFrom<&str> for Connection {
@@ -23,66 +38,46 @@ impl TryFrom<&str> for Connection {
             "WH" => Ok(Connection::Wheel),
             "NS" => Ok(Connection::NormalStraight),
             "AS" => Ok(Connection::AnyStraight),
+            "1S" => Ok(Connection::OneCardStraight),
+            "BS" => Ok(Connection::BoardStraight),
             _ => Err(ParseError::str("connection", s)),
         }
     }
 }
 
+// Classified by the holdings that make a straight or draw to one, see Connectivity
 impl Board {
     pub fn is_normal_straight_possible(&self) -> bool {
-        let mut cards_vec: Vec<_> = self.cards.iter().collect();
-        cards_vec.dedup_by(|card1, card2| card1.rank == card2.rank);
-
-        cards_vec.windows(3).any(|three_cards| {
-            if let [low_card, _, high_card] = three_cards {
-                (*high_card - *low_card) <= 4
-            } else {
-                unreachable!()
-            }
-        })
+        self.num_straight_holdings(&STRAIGHTS[1..]) > 0
     }
 
     pub fn is_wheel_possible(&self) -> bool {
-        self.num_rank_height(&RankHeight::Wheel) == 3
+        self.num_straight_holdings(&[RankHeight::Wheel.mask()]) > 0
     }
 
     pub fn is_any_straight_possible(&self) -> bool {
-        self.is_normal_straight_possible() || self.is_wheel_possible()
+        self.get_connectivity().is_any_straight_possible()
     }
 
     pub fn is_only_oesd_possible(&self) -> bool {
-        if self.is_any_straight_possible() {
-            return false;
-        }
-
-        Card::get_distances(self.cards.iter().filter(|card| !card.is_ace()))
-            .iter()
-            .any(|diff| (1..=3).contains(diff))
+        self.get_connectivity().is_only_oesd_possible()
     }
 
     pub fn is_only_gutshot_possible(&self) -> bool {
-        if self.is_any_straight_possible() || self.is_only_oesd_possible() {
-            return false;
-        }
-
-        let distances = Card::get_distances(self.cards.iter());
-        let min_distance = *distances.iter().min().unwrap();
-
-        min_distance <= 4
+        self.get_connectivity().is_only_gutshot_possible()
     }
 
     pub fn is_disconnected(&self) -> bool {
-        if self.get_highest_card().is_ace() {
-            return false;
-        }
+        self.get_connectivity().is_disconnected()
+    }
 
-        let distances = Card::get_distances(self.cards.iter());
-        let min_distance = distances.iter().min().unwrap();
-        if *min_distance <= 4 {
-            return false;
-        }
+    // Four cards of one straight, so a single hole card completes it
+    pub fn is_one_card_straight_possible(&self) -> bool {
+        self.get_max_ranks_in_straight() >= 4
+    }
 
-        true
+    pub fn is_straight_on_board(&self) -> bool {
+        self.get_max_ranks_in_straight() == 5
     }
 
     pub fn is_connection(&self, connection: &Connection) -> bool {
@@ -93,18 +88,37 @@ impl Board {
             Connection::Wheel => self.is_wheel_possible(),
             Connection::NormalStraight => self.is_normal_straight_possible(),
             Connection::AnyStraight => self.is_any_straight_possible(),
+            Connection::OneCardStraight => self.is_one_card_straight_possible(),
+            Connection::BoardStraight => self.is_straight_on_board(),
+        }
+    }
+
+    // For callers checking every class, which then count the holdings once
+    pub fn is_connection_with(&self, connection: &Connection, connectivity: &Connectivity) -> bool {
+        match connection {
+            Connection::Disconnected => connectivity.is_disconnected(),
+            Connection::Gutshot => connectivity.is_only_gutshot_possible(),
+            Connection::Oesd => connectivity.is_only_oesd_possible(),
+            Connection::AnyStraight => connectivity.is_any_straight_possible(),
+            _ => self.is_connection(connection),
         }
     }
 
-    fn get_highest_card(&self) -> &Card {
-        self.cards.last().unwrap()
+    fn get_max_ranks_in_straight(&self) -> usize {
+        STRAIGHTS
+            .iter()
+            .map(|straight| self.num_ranks_in(*straight))
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn num_ranks_in(&self, straight: u16) -> usize {
+        (self.get_rank_mask() & straight).count_ones() as usize
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::poker::rank::Rank;
-
     use super::*;
 
     #[test]
@@ -227,7 +241,8 @@ mod tests {
         assert!(Board::try_from("AcKh6h")
             .unwrap()
             .is_only_gutshot_possible());
-        assert!(!Board::try_from("Ac9h4h")
+        // A4 with 32 draws to the wheel
+        assert!(Board::try_from("Ac9h4h")
             .unwrap()
             .is_only_gutshot_possible());
         assert!(!Board::try_from("AcKhTh")
@@ -255,6 +270,8 @@ mod tests {
         assert!(Board::try_from("Kh8h2h").unwrap().is_disconnected());
         assert!(Board::try_from("Kh7c2c").unwrap().is_disconnected());
         assert!(Board::try_from("Qh7c2c").unwrap().is_disconnected());
+        assert!(Board::try_from("Ah8c8d").unwrap().is_disconnected());
+        assert!(Board::try_from("KhKc2d").unwrap().is_disconnected());
         assert!(!Board::try_from("Kh6c2d").unwrap().is_disconnected());
         assert!(!Board::try_from("Ah6c9d").unwrap().is_disconnected());
         assert!(!Board::try_from("Kh3c9d").unwrap().is_disconnected());
@@ -316,17 +333,55 @@ mod tests {
     }
 
     #[test]
-    fn test_get_highest_card() {
-        assert_eq!(
-            Board::try_from("Kh8h2h").unwrap().get_highest_card(),
-            &Card::try_from("Kh").unwrap()
-        );
-        assert_eq!(
-            Board::try_from("AsKhTs").unwrap().get_highest_card(),
-            &Card::try_from("As").unwrap()
-        );
-
-        let board = Board::try_from("KhKs8c").unwrap();
-        assert!(board.get_highest_card().rank == Rank::try_from('K').unwrap());
+    fn test_turn_and_river_straights() {
+        assert!(Board::try_from("Ks4d3c2h").unwrap().is_wheel_possible());
+        assert!(Board::try_from("Ks9d7c6h")
+            .unwrap()
+            .is_normal_straight_possible());
+        assert!(!Board::try_from("KsKd7c2h")
+            .unwrap()
+            .is_any_straight_possible());
+
+        assert!(Board::try_from("9s8d7c6h")
+            .unwrap()
+            .is_one_card_straight_possible());
+        assert!(Board::try_from("Js9d8c7h")
+            .unwrap()
+            .is_one_card_straight_possible());
+        assert!(Board::try_from("As2d3c4h")
+            .unwrap()
+            .is_one_card_straight_possible());
+        assert!(Board::try_from("AsKdQcJh")
+            .unwrap()
+            .is_one_card_straight_possible());
+        assert!(!Board::try_from("9s8d7c2h")
+            .unwrap()
+            .is_one_card_straight_possible());
+        assert!(!Board::try_from("9s8d7c6h").unwrap().is_straight_on_board());
+
+        assert!(Board::try_from("9s8d7c6h5h")
+            .unwrap()
+            .is_straight_on_board());
+        assert!(Board::try_from("As2d3c4h5h")
+            .unwrap()
+            .is_straight_on_board());
+        assert!(Board::try_from("AsKdQcJhTh")
+            .unwrap()
+            .is_straight_on_board());
+        assert!(!Board::try_from("9s8d7c6h6d")
+            .unwrap()
+            .is_straight_on_board());
+        assert!(!Board::try_from("KsAd2c3h4h")
+            .unwrap()
+            .is_straight_on_board());
+    }
+
+    #[test]
+    fn test_is_connection_turn() {
+        let board = Board::try_from("Js9d8c7h").unwrap();
+        assert!(board.is_connection(&Connection::OneCardStraight));
+        assert!(board.is_connection(&Connection::NormalStraight));
+        assert!(!board.is_connection(&Connection::BoardStraight));
+        assert!(!board.is_connection(&Connection::Wheel));
     }
 }
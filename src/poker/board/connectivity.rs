@@ -0,0 +1,353 @@
+use std::str::FromStr;
+
+use crate::poker::{rank::Rank, ParseError};
+
+use super::{Board, STRAIGHTS};
+
+/*
+ * How connected a board is, counted over the two-card holdings by rank, e.g. QJ, J7 and 76
+ * make a straight on T98. Holdings that need a card the board already holds all of are left out.
+ * A draw is a holding without a straight that a single card completes, open-ended with two or
+ * more ranks to hit and a gutshot with one. Ranks that complete a straight on the board alone
+ * do not count, so 9876 gives nobody a draw with a T or a 5. The river has no draws.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Connectivity {
+    pub straights: usize,
+    pub open_enders: usize,
+    pub gutshots: usize,
+    // Largest difference between neighbouring ranks, 0 on boards with a single rank. The ace
+    // counts as low when that makes the gap smaller, so A42 has a gap of 2.
+    pub max_gap: usize,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ConnectivityMetric {
+    Straights,
+    OpenEnders,
+    Gutshots,
+    MaxGap,
+}
+
+impl TryFrom<&str> for ConnectivityMetric {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<ConnectivityMetric, ParseError> {
+        match s {
+            "SH" => Ok(ConnectivityMetric::Straights),
+            "OE" => Ok(ConnectivityMetric::OpenEnders),
+            "GS" => Ok(ConnectivityMetric::Gutshots),
+            "GAP" => Ok(ConnectivityMetric::MaxGap),
+            _ => Err(ParseError::str("connectivity metric", s)),
+        }
+    }
+}
+
+// A count (5), a count or more (5+), a count or less (5-) or two counts (3-8)
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct CountRange {
+    pub low: usize,
+    pub high: usize,
+}
+
+impl FromStr for CountRange {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<CountRange, ParseError> {
+        let count = |s: &str| s.parse().map_err(|_| ParseError::str("count range", s));
+
+        let (count1, count2) = if let Some(low) = s.strip_suffix('+') {
+            (count(low)?, usize::MAX)
+        } else if let Some(high) = s.strip_suffix('-') {
+            (0, count(high)?)
+        } else if let Some((count1, count2)) = s.split_once('-') {
+            (count(count1)?, count(count2)?)
+        } else {
+            (count(s)?, count(s)?)
+        };
+
+        Ok(CountRange {
+            low: count1.min(count2),
+            high: count1.max(count2),
+        })
+    }
+}
+
+impl CountRange {
+    pub fn contains(&self, count: usize) -> bool {
+        (self.low..=self.high).contains(&count)
+    }
+}
+
+// A metric within a range, written as metric:range, e.g. SH:10+ or GAP:3-
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct ConnectivityRange {
+    pub metric: ConnectivityMetric,
+    pub range: CountRange,
+}
+
+impl FromStr for ConnectivityRange {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<ConnectivityRange, ParseError> {
+        let (metric, range) = s
+            .split_once(':')
+            .ok_or_else(|| ParseError::str("connectivity range", s))?;
+
+        Ok(ConnectivityRange {
+            metric: ConnectivityMetric::try_from(metric)?,
+            range: CountRange::from_str(range)?,
+        })
+    }
+}
+
+/*
+ * The connection classes by the counts: a board without straights is an OESD board when some
+ * holding has an open-ended draw, a gutshot board when some holding has a gutshot and
+ * disconnected otherwise
+ */
+impl Connectivity {
+    pub fn is_any_straight_possible(&self) -> bool {
+        self.straights > 0
+    }
+
+    pub fn is_only_oesd_possible(&self) -> bool {
+        self.straights == 0 && self.open_enders > 0
+    }
+
+    pub fn is_only_gutshot_possible(&self) -> bool {
+        self.straights == 0 && self.open_enders == 0 && self.gutshots > 0
+    }
+
+    pub fn is_disconnected(&self) -> bool {
+        self.straights == 0 && self.open_enders == 0 && self.gutshots == 0
+    }
+}
+
+impl Board {
+    pub fn get_connectivity(&self) -> Connectivity {
+        let ranks = self.get_rank_mask();
+        let board_outs = get_outs(ranks);
+        let mut connectivity = Connectivity {
+            straights: 0,
+            open_enders: 0,
+            gutshots: 0,
+            max_gap: get_max_gap(ranks),
+        };
+
+        for (holding, gone) in self.get_holdings() {
+            let with_holding = ranks | holding;
+
+            if makes_straight(with_holding, &STRAIGHTS) {
+                connectivity.straights += 1;
+                continue;
+            }
+
+            if self.num_cards() == 5 {
+                continue;
+            }
+
+            match (get_outs(with_holding) & !board_outs & !gone).count_ones() {
+                0 => {}
+                1 => connectivity.gutshots += 1,
+                _ => connectivity.open_enders += 1,
+            }
+        }
+
+        connectivity
+    }
+
+    pub fn is_connectivity(&self, range: &ConnectivityRange) -> bool {
+        let connectivity = self.get_connectivity();
+        let count = match range.metric {
+            ConnectivityMetric::Straights => connectivity.straights,
+            ConnectivityMetric::OpenEnders => connectivity.open_enders,
+            ConnectivityMetric::Gutshots => connectivity.gutshots,
+            ConnectivityMetric::MaxGap => connectivity.max_gap,
+        };
+
+        range.range.contains(count)
+    }
+
+    // Holdings that make one of the given straights
+    pub(super) fn num_straight_holdings(&self, straights: &[u16]) -> usize {
+        let ranks = self.get_rank_mask();
+        self.get_holdings()
+            .filter(|(holding, _)| makes_straight(ranks | holding, straights))
+            .count()
+    }
+
+    /*
+     * Every pair of ranks as a rank mask, pocket pairs included, with enough cards left
+     * off the board. Each comes with the ranks of which no card is left once it is dealt.
+     */
+    fn get_holdings(&self) -> impl Iterator<Item = (u16, u16)> {
+        let num_ranks = Rank::ALL.len();
+        let at_least = |num_cards| {
+            Rank::ALL
+                .iter()
+                .filter(|rank| self.num_rank(**rank) >= num_cards)
+                .fold(0, |ranks, rank| ranks | rank.mask())
+        };
+        let (two, three, four) = (at_least(2), at_least(3), at_least(4));
+
+        (0..num_ranks)
+            .flat_map(move |i| (i..num_ranks).map(move |j| (i, j)))
+            .filter_map(move |(i, j)| {
+                let holding: u16 = 1 << i | 1 << j;
+                let (blocked, gone) = if i == j { (three, two) } else { (four, three) };
+                (holding & blocked == 0).then_some((holding, four | gone & holding))
+            })
+    }
+}
+
+fn makes_straight(ranks: u16, straights: &[u16]) -> bool {
+    straights
+        .iter()
+        .any(|straight| ranks & straight == *straight)
+}
+
+// The ranks that complete a straight when added to a rank mask
+fn get_outs(ranks: u16) -> u16 {
+    STRAIGHTS
+        .iter()
+        .map(|straight| straight & !ranks)
+        .filter(|missing| missing.count_ones() == 1)
+        .fold(0, |outs, missing| outs | missing)
+}
+
+// The smaller of the largest gaps with the ace high and with the ace below the deuce
+fn get_max_gap(ranks: u16) -> usize {
+    let ace = Rank::A.mask();
+    let ace_low = (ranks & !ace) << 1 | (ranks & ace) >> ace.trailing_zeros();
+
+    [ranks, ace_low]
+        .into_iter()
+        .map(|ranks| get_rank_gaps(ranks).max().unwrap_or_default())
+        .min()
+        .unwrap_or_default() as usize
+}
+
+// Differences between neighbouring ranks of a rank mask, from the lowest rank up
+fn get_rank_gaps(ranks: u16) -> impl Iterator<Item = u32> {
+    let mut rest = ranks;
+    let mut previous: Option<u32> = None;
+
+    std::iter::from_fn(move || {
+        while rest != 0 {
+            let rank = rest.trailing_zeros();
+            rest &= rest - 1;
+
+            if let Some(previous) = previous.replace(rank) {
+                return Some(rank - previous);
+            }
+        }
+
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connectivity(board: &str) -> Connectivity {
+        Board::try_from(board).unwrap().get_connectivity()
+    }
+
+    #[test]
+    fn test_count_range_from_str() {
+        assert_eq!(
+            CountRange::from_str("5").unwrap(),
+            CountRange { low: 5, high: 5 }
+        );
+        assert_eq!(
+            CountRange::from_str("10+").unwrap(),
+            CountRange {
+                low: 10,
+                high: usize::MAX
+            }
+        );
+        assert_eq!(
+            CountRange::from_str("3-").unwrap(),
+            CountRange { low: 0, high: 3 }
+        );
+        assert_eq!(
+            CountRange::from_str("8-3").unwrap(),
+            CountRange { low: 3, high: 8 }
+        );
+        assert!(CountRange::from_str("").is_err());
+        assert!(CountRange::from_str("x").is_err());
+        assert!(CountRange::from_str("3-x").is_err());
+    }
+
+    #[test]
+    fn test_connectivity_range_from_str() {
+        assert_eq!(
+            ConnectivityRange::from_str("GAP:3-").unwrap(),
+            ConnectivityRange {
+                metric: ConnectivityMetric::MaxGap,
+                range: CountRange { low: 0, high: 3 }
+            }
+        );
+        assert!(ConnectivityRange::from_str("SH").is_err());
+        assert!(ConnectivityRange::from_str("XX:3").is_err());
+    }
+
+    #[test]
+    fn test_straights() {
+        // QJ, J7 and 76
+        assert_eq!(connectivity("Ts9d8c").straights, 3);
+        // 53 for the wheel
+        assert_eq!(connectivity("As4d2c").straights, 1);
+        // Every holding with a 7 or a 2
+        assert_eq!(connectivity("6s5d4c3h").straights, 25);
+        assert_eq!(connectivity("Ks8d2c").straights, 0);
+    }
+
+    #[test]
+    fn test_draws() {
+        let connectivity = connectivity("KhTh2c");
+        assert_eq!(connectivity.straights, 0);
+        // QJ
+        assert_eq!(connectivity.open_enders, 1);
+        // AQ, AJ, Q9 and J9
+        assert_eq!(connectivity.gutshots, 4);
+
+        let connectivity = self::connectivity("Kh8h2h");
+        assert_eq!(connectivity.open_enders, 0);
+        assert_eq!(connectivity.gutshots, 0);
+
+        // Only a T or a 5, which complete the board by themselves
+        assert_eq!(self::connectivity("9s8d7c6h").open_enders, 0);
+        assert_eq!(self::connectivity("Ks8d7c2hTh").gutshots, 0);
+    }
+
+    #[test]
+    fn test_max_gap() {
+        assert_eq!(connectivity("Ks8d2c").max_gap, 6);
+        assert_eq!(connectivity("Ts9d8c").max_gap, 1);
+        assert_eq!(connectivity("7s7d7c").max_gap, 0);
+
+        // The ace is low on wheel boards and high on broadway boards
+        assert_eq!(connectivity("As4d2c").max_gap, 2);
+        assert_eq!(connectivity("AsAd5c").max_gap, 4);
+        assert_eq!(connectivity("AsKdQc").max_gap, 1);
+        assert_eq!(connectivity("AsKd2c").max_gap, 11);
+    }
+
+    #[test]
+    fn test_is_connectivity() {
+        let board = Board::try_from("Ts9d8c").unwrap();
+        assert!(board.is_connectivity(&ConnectivityRange::from_str("SH:3").unwrap()));
+        assert!(board.is_connectivity(&ConnectivityRange::from_str("GAP:1-").unwrap()));
+        assert!(!board.is_connectivity(&ConnectivityRange::from_str("SH:4+").unwrap()));
+    }
+
+    #[test]
+    fn test_get_rank_gaps() {
+        let ranks = Board::try_from("Qc7hTh7c").unwrap().get_rank_mask();
+        assert_eq!(get_rank_gaps(ranks).collect::<Vec<_>>(), vec![3, 2]);
+        assert_eq!(get_rank_gaps(Rank::A.mask()).count(), 0);
+    }
+}
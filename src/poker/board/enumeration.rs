@@ -0,0 +1,77 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use crate::poker::card::Card;
+
+use super::Board;
+
+pub const NUM_FLOPS: usize = 22_100;
+
+pub fn get_all_flops() -> Vec<Board> {
+    let deck = Card::get_deck();
+    let mut flops = Vec::with_capacity(NUM_FLOPS);
+
+    for (i, card1) in deck.iter().enumerate() {
+        for (j, card2) in deck.iter().enumerate().skip(i + 1) {
+            for card3 in deck.iter().skip(j + 1) {
+                flops.push(Board {
+                    mask: card1.mask() | card2.mask() | card3.mask(),
+                    turn: None,
+                    river: None,
+                });
+            }
+        }
+    }
+
+    flops
+}
+
+/*
+ * The strategically distinct flops, each with the number of flops it stands for.
+ * Sorted from the highest to the lowest board.
+ */
+pub fn get_canonical_flops() -> Vec<(Board, usize)> {
+    let mut frequencies: HashMap<Board, usize> = HashMap::new();
+
+    for flop in get_all_flops() {
+        *frequencies.entry(flop.canonical()).or_default() += 1;
+    }
+
+    let mut canonical_flops: Vec<(Board, usize)> = frequencies.into_iter().collect();
+    canonical_flops.sort_by_key(|(flop, _)| Reverse(flop.mask));
+
+    canonical_flops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_all_flops() {
+        let flops = get_all_flops();
+
+        assert_eq!(flops.len(), NUM_FLOPS);
+        assert!(flops.iter().all(|flop| flop.num_cards() == 3));
+    }
+
+    #[test]
+    fn test_get_canonical_flops() {
+        let canonical_flops = get_canonical_flops();
+
+        assert_eq!(canonical_flops.len(), 1_755);
+        assert_eq!(
+            canonical_flops.iter().map(|(_, freq)| freq).sum::<usize>(),
+            NUM_FLOPS
+        );
+        assert!(canonical_flops
+            .iter()
+            .all(|(flop, freq)| flop.num_isomorphic_variants() == *freq));
+
+        let (highest, freq) = &canonical_flops[0];
+        assert_eq!(
+            highest.to_string().chars().step_by(2).collect::<String>(),
+            "AAA"
+        );
+        assert_eq!(*freq, 4);
+    }
+}
@@ -0,0 +1,181 @@
+use std::str::FromStr;
+
+use crate::poker::{
+    rank::{Rank, RankHeight},
+    suit::Suit,
+    ParseError,
+};
+
+use super::{get_rank_mask, Board, SPADES};
+
+/*
+ * Boards by the cards that carry a flush draw, e.g. AsTs4d has an ace-high draw
+ * and Kd7s4s the two lowest cards suited. The draw suits are the suits with two or
+ * three cards on the flop or turn, a board can have several and matches a class
+ * when any of them does.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum FlushDraw {
+    // The ace is one of the suited cards
+    AceHigh,
+    // At least two broadway cards are suited
+    Broadway,
+    // The suited cards are all lower than the other cards
+    Low,
+    // The highest card of a draw suit that is not on the board, which makes the nut draw,
+    // e.g. the K on AsTs4d
+    Nut(Rank),
+    // The number of suits with a single card on the flop, two suited cards of one of
+    // them make a backdoor draw
+    Backdoor(usize),
+}
+
+impl TryFrom<&str> for FlushDraw {
+    type Error = ParseError;
+
+    // Nut and backdoor draws take a value after a colon, e.g. NUT:K or BD:3
+    fn try_from(s: &str) -> Result<FlushDraw, ParseError> {
+        let err = || ParseError::str("flush draw", s);
+
+        match s.split_once(':') {
+            Some(("NUT", rank)) => Ok(FlushDraw::Nut(Rank::from_str(rank)?)),
+            Some(("BD", num_suits)) => match num_suits.parse() {
+                Ok(num_suits) if num_suits <= 3 => Ok(FlushDraw::Backdoor(num_suits)),
+                _ => Err(err()),
+            },
+            Some(_) => Err(err()),
+            None => match s {
+                "A" => Ok(FlushDraw::AceHigh),
+                "BW" => Ok(FlushDraw::Broadway),
+                "LOW" => Ok(FlushDraw::Low),
+                _ => Err(err()),
+            },
+        }
+    }
+}
+
+impl Board {
+    pub fn is_flush_draw(&self, flush_draw: &FlushDraw) -> bool {
+        match flush_draw {
+            FlushDraw::AceHigh => self
+                .get_draw_suits()
+                .any(|(suited, _)| suited & Rank::A.mask() != 0),
+            FlushDraw::Broadway => self
+                .get_draw_suits()
+                .any(|(suited, _)| (suited & RankHeight::Broadway.mask()).count_ones() >= 2),
+            // Below the lowest other rank, so never on a monotone flop
+            FlushDraw::Low => self
+                .get_draw_suits()
+                .any(|(suited, others)| others != 0 && suited < 1 << others.trailing_zeros()),
+            FlushDraw::Nut(rank) => self.get_nut_draw_ranks().any(|nut| nut == *rank),
+            FlushDraw::Backdoor(num_suits) => self.num_backdoor_suits() == *num_suits,
+        }
+    }
+
+    // The nut card of each draw suit, the highest rank of the suit not on the board
+    pub fn get_nut_draw_ranks(&self) -> impl Iterator<Item = Rank> + '_ {
+        self.get_draw_suits().filter_map(|(suited, _)| {
+            Rank::ALL
+                .into_iter()
+                .rev()
+                .find(|rank| suited & rank.mask() == 0)
+        })
+    }
+
+    // Backdoor draws need the turn and river, so turns and rivers have none
+    pub fn num_backdoor_suits(&self) -> usize {
+        if self.num_cards() != 3 {
+            return 0;
+        }
+
+        Suit::ALL
+            .iter()
+            .filter(|suit| self.num_suit(**suit) == 1)
+            .count()
+    }
+
+    // The rank masks of the suited cards and of the other cards for each draw suit
+    fn get_draw_suits(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        Suit::ALL
+            .into_iter()
+            .filter(|suit| self.num_cards() < 5 && matches!(self.num_suit(*suit), 2 | 3))
+            .map(|suit| {
+                let suit_cards = SPADES << suit as u32;
+                (
+                    get_rank_mask(self.mask & suit_cards),
+                    get_rank_mask(self.mask & !suit_cards),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_flush_draw(board: &str, flush_draw: FlushDraw) -> bool {
+        Board::try_from(board).unwrap().is_flush_draw(&flush_draw)
+    }
+
+    #[test]
+    fn test_flush_draw_try_from() {
+        assert_eq!(FlushDraw::try_from("A").unwrap(), FlushDraw::AceHigh);
+        assert_eq!(
+            FlushDraw::try_from("NUT:K").unwrap(),
+            FlushDraw::Nut(Rank::K)
+        );
+        assert_eq!(FlushDraw::try_from("BD:3").unwrap(), FlushDraw::Backdoor(3));
+        assert!(FlushDraw::try_from("X").is_err());
+        assert!(FlushDraw::try_from("NUT").is_err());
+        assert!(FlushDraw::try_from("BD:4").is_err());
+        assert!(FlushDraw::try_from("A:K").is_err());
+    }
+
+    #[test]
+    fn test_is_ace_high_and_nut() {
+        assert!(is_flush_draw("AsTs4d", FlushDraw::AceHigh));
+        assert!(!is_flush_draw("AsTd4d", FlushDraw::AceHigh));
+        assert!(!is_flush_draw("AsTd4c", FlushDraw::AceHigh));
+
+        // KsXs is the nut draw
+        assert!(is_flush_draw("AsTs4d", FlushDraw::Nut(Rank::K)));
+        assert!(!is_flush_draw("AsTs4d", FlushDraw::Nut(Rank::A)));
+        assert!(is_flush_draw("AsKsTs", FlushDraw::Nut(Rank::Q)));
+        assert!(is_flush_draw("AsTd4d", FlushDraw::Nut(Rank::A)));
+        assert!(!is_flush_draw("AsTd4c", FlushDraw::Nut(Rank::A)));
+        assert!(is_flush_draw("AsTs4d2d", FlushDraw::Nut(Rank::K)));
+        assert!(is_flush_draw("AsTs4d2d", FlushDraw::Nut(Rank::A)));
+    }
+
+    #[test]
+    fn test_is_broadway_and_low() {
+        assert!(is_flush_draw("KsQs2d", FlushDraw::Broadway));
+        assert!(is_flush_draw("KsQs2s", FlushDraw::Broadway));
+        assert!(!is_flush_draw("KsQd2s", FlushDraw::Broadway));
+
+        assert!(is_flush_draw("Kd7s4s", FlushDraw::Low));
+        assert!(!is_flush_draw("Ks7d4s", FlushDraw::Low));
+        assert!(!is_flush_draw("7c7s4s", FlushDraw::Low));
+        assert!(!is_flush_draw("Ks7s4s", FlushDraw::Low));
+        assert!(is_flush_draw("KdJc7s4s", FlushDraw::Low));
+    }
+
+    #[test]
+    fn test_is_backdoor() {
+        assert!(is_flush_draw("Ks7d4c", FlushDraw::Backdoor(3)));
+        assert!(is_flush_draw("Ks7s4c", FlushDraw::Backdoor(1)));
+        assert!(is_flush_draw("Ks7s4s", FlushDraw::Backdoor(0)));
+        assert!(!is_flush_draw("Ks7d4c", FlushDraw::Backdoor(1)));
+        assert!(is_flush_draw("Ks7d4c2h", FlushDraw::Backdoor(0)));
+    }
+
+    #[test]
+    fn test_no_draws_on_the_river() {
+        let board = Board::try_from("Ks7s4d2c9h").unwrap();
+        assert!(!board.is_flush_draw(&FlushDraw::AceHigh));
+        assert!(!board.is_flush_draw(&FlushDraw::Broadway));
+        assert!(!board.is_flush_draw(&FlushDraw::Low));
+        assert_eq!(board.get_nut_draw_ranks().count(), 0);
+        assert_eq!(board.num_backdoor_suits(), 0);
+    }
+}
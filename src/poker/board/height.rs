@@ -1,7 +1,7 @@
 use super::Board;
 use crate::poker::{rank::RankHeight, ParseError};
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum BoardHeight {
     TripleBW,
     DoubleBW,
@@ -10,6 +10,16 @@ pub enum BoardHeight {
     Low,
 }
 
+impl BoardHeight {
+    pub const ALL: [BoardHeight; 5] = [
+        BoardHeight::TripleBW,
+        BoardHeight::DoubleBW,
+        BoardHeight::SingleBW,
+        BoardHeight::Middling,
+        BoardHeight::Low,
+    ];
+}
+
 impl TryFrom<&str> for BoardHeight {
     type Error = ParseError;
 
@@ -26,8 +36,9 @@ impl TryFrom<&str> for BoardHeight {
 }
 
 impl Board {
+    // Turn and river boards with three or more broadway cards count as 3BW
     pub fn is_3bw(&self) -> bool {
-        self.num_rank_height(&RankHeight::Broadway) == 3
+        self.num_rank_height(&RankHeight::Broadway) >= 3
     }
 
     pub fn is_2bw(&self) -> bool {
@@ -44,7 +55,7 @@ impl Board {
     }
 
     pub fn is_low(&self) -> bool {
-        self.num_rank_height(&RankHeight::Low) == 3
+        self.num_rank_height(&RankHeight::Low) == self.num_cards()
     }
 
     pub fn is_height(&self, height: &BoardHeight) -> bool {
@@ -151,4 +162,16 @@ mod tests {
             .unwrap()
             .is_height(&BoardHeight::Low));
     }
+
+    #[test]
+    fn test_turn_and_river_heights() {
+        assert!(Board::try_from("AsKdQh2c").unwrap().is_3bw());
+        assert!(Board::try_from("AsKdQhJc2c").unwrap().is_3bw());
+        assert!(Board::try_from("AsKd7h2c").unwrap().is_2bw());
+        assert!(Board::try_from("As8d7h2c3d").unwrap().is_1bw());
+        assert!(Board::try_from("9s8d7h2c").unwrap().is_middling());
+        assert!(Board::try_from("6s5d4h2c").unwrap().is_low());
+        assert!(Board::try_from("6s5d4h2c3c").unwrap().is_low());
+        assert!(!Board::try_from("6s5d4h2c7c").unwrap().is_low());
+    }
 }
@@ -0,0 +1,88 @@
+use crate::poker::rank::{Rank, RankRange};
+
+use super::Board;
+
+/*
+ * Boards by their ranks, e.g. A-high, K-high with a second card of T or lower.
+ * The second card is the second highest rank, so it is the kicker of KK4.
+ */
+impl Board {
+    pub fn get_highest_rank(&self) -> Rank {
+        get_highest(self.get_rank_mask()).expect("A board has at least three cards")
+    }
+
+    // None on boards with a single rank, such as trips on the flop
+    pub fn get_second_rank(&self) -> Option<Rank> {
+        let ranks = self.get_rank_mask();
+        get_highest(ranks & !self.get_highest_rank().mask())
+    }
+
+    pub fn is_high_card(&self, range: &RankRange) -> bool {
+        range.contains(self.get_highest_rank())
+    }
+
+    pub fn is_second_card(&self, range: &RankRange) -> bool {
+        self.get_second_rank()
+            .is_some_and(|rank| range.contains(rank))
+    }
+
+    pub fn contains_rank(&self, rank: Rank) -> bool {
+        self.get_rank_mask() & rank.mask() != 0
+    }
+}
+
+fn get_highest(ranks: u16) -> Option<Rank> {
+    Rank::ALL
+        .get((u16::BITS - 1).checked_sub(ranks.leading_zeros())? as usize)
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn board(s: &str) -> Board {
+        Board::try_from(s).unwrap()
+    }
+
+    fn range(s: &str) -> RankRange {
+        RankRange::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_get_highest_and_second_rank() {
+        assert_eq!(board("Ks7d2c").get_highest_rank(), Rank::K);
+        assert_eq!(board("Ks7d2c").get_second_rank(), Some(Rank::_7));
+        assert_eq!(board("KsKd4c").get_second_rank(), Some(Rank::_4));
+        assert_eq!(board("4s4dKc").get_second_rank(), Some(Rank::_4));
+        assert_eq!(board("7s7d7c").get_highest_rank(), Rank::_7);
+        assert_eq!(board("7s7d7c").get_second_rank(), None);
+        assert_eq!(board("7s7d7cAh").get_second_rank(), Some(Rank::_7));
+    }
+
+    #[test]
+    fn test_is_high_card() {
+        assert!(board("As7d2c").is_high_card(&range("A")));
+        assert!(!board("Ks7d2c").is_high_card(&range("A")));
+        assert!(board("Ks7d2c").is_high_card(&range("Q+")));
+        assert!(board("Js7d2c").is_high_card(&range("T-Q")));
+        assert!(board("9s7d2c").is_high_card(&range("9-")));
+        assert!(!board("Ts7d2c").is_high_card(&range("9-")));
+    }
+
+    #[test]
+    fn test_is_second_card() {
+        assert!(board("AsKd2c").is_second_card(&range("K")));
+        assert!(board("As9d2c").is_second_card(&range("T-")));
+        assert!(!board("AsAdAc").is_second_card(&range("2+")));
+    }
+
+    #[test]
+    fn test_contains_rank() {
+        assert!(board("Ks7dAc").contains_rank(Rank::A));
+        assert!(board("Ks7d2cAh").contains_rank(Rank::A));
+        assert!(!board("Ks7d2c").contains_rank(Rank::A));
+    }
+}
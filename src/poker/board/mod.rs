@@ -1,57 +1,158 @@
+pub mod canonical;
 pub mod connection;
+pub mod connectivity;
+pub mod enumeration;
+pub mod flush;
 pub mod height;
+pub mod high_card;
 pub mod pair;
+pub mod pattern;
 pub mod suit;
+pub mod texture;
+pub mod turn;
 
-use std::{
-    collections::BTreeSet,
-    fmt::{self, Display, Formatter},
-};
+use std::fmt::{self, Display, Formatter};
 
-use super::{card::Card, rank::RankHeight, ParseError};
+use super::{
+    card::Card,
+    rank::{Rank, RankHeight},
+    suit::Suit,
+    ParseError,
+};
 
-#[derive(Debug, Hash, Eq, PartialEq)]
+/*
+ * The cards of a board as a 52-bit mask with the bit of each card at its index,
+ * so the four cards of a rank form a nibble and each suit every fourth bit.
+ * Classifiers work on the mask and the rank masks derived from it.
+ */
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub struct Board {
-    pub cards: BTreeSet<Card>,
+    mask: u64,
+    // The mask loses the order the cards were dealt in, so later streets are kept apart
+    pub turn: Option<Card>,
+    pub river: Option<Card>,
 }
 
+const MIN_NUM_CARDS: usize = 3;
+const MAX_NUM_CARDS: usize = 5;
+
+// The bits of all spades, shifted by the suit for the other suits
+const SPADES: u64 = 0x1_1111_1111_1111;
+const RANK_CARDS: u64 = 0b1111;
+
+// Rank masks of every straight from the wheel (A2345) to broadway (TJQKA)
+const STRAIGHTS: [u16; 10] = [
+    0b1_0000_0000_1111,
+    0b1_1111,
+    0b11_1110,
+    0b111_1100,
+    0b1111_1000,
+    0b1_1111_0000,
+    0b11_1110_0000,
+    0b111_1100_0000,
+    0b1111_1000_0000,
+    0b1_1111_0000_0000,
+];
+
 impl TryFrom<&str> for Board {
     type Error = ParseError;
 
+    // Flops have three cards, turns four and rivers five
     fn try_from(board_str: &str) -> Result<Board, ParseError> {
-        let expected_num_cards = 3;
-        let expected_length = expected_num_cards * 2;
-        if board_str.len() != expected_length {
-            return Err(ParseError::str("flop", board_str));
+        let length = board_str.len();
+        let num_cards = length / 2;
+        if !length.is_multiple_of(2) || !(MIN_NUM_CARDS..=MAX_NUM_CARDS).contains(&num_cards) {
+            return Err(ParseError::str("board", board_str));
         }
 
-        let cards: BTreeSet<Card> = (0..expected_length)
+        let dealt: Vec<Card> = (0..length)
             .step_by(2)
-            .map(|i| &board_str[i..i + 2])
+            .map(|i| board_str.get(i..i + 2).unwrap_or_default())
             .map(Card::try_from)
             .collect::<Result<_, _>>()?;
 
-        assert_eq!(cards.len(), expected_num_cards);
+        let mask = dealt.iter().fold(0, |mask, card| mask | card.mask());
+        if mask.count_ones() as usize != num_cards {
+            return Err(ParseError::str("board", board_str));
+        }
 
-        Ok(Board { cards })
+        Ok(Board {
+            mask,
+            turn: dealt.get(3).copied(),
+            river: dealt.get(4).copied(),
+        })
     }
 }
 
 impl Board {
-    fn num_rank_height(&self, height: &RankHeight) -> usize {
-        self.cards
+    pub fn num_cards(&self) -> usize {
+        self.mask.count_ones() as usize
+    }
+
+    // From the lowest to the highest card
+    pub fn cards(&self) -> Cards {
+        Cards(self.mask)
+    }
+
+    pub fn get_flop(&self) -> Board {
+        let later_streets = self
+            .turn
             .iter()
-            .filter(|card| card.is_height(height))
-            .count()
+            .chain(&self.river)
+            .fold(0, |mask, card| mask | card.mask());
+
+        Board {
+            mask: self.mask & !later_streets,
+            turn: None,
+            river: None,
+        }
     }
+
+    // One bit per rank on the board, see Rank::mask
+    pub fn get_rank_mask(&self) -> u16 {
+        get_rank_mask(self.mask)
+    }
+
+    fn num_rank(&self, rank: Rank) -> usize {
+        (self.mask >> (4 * rank as u32) & RANK_CARDS).count_ones() as usize
+    }
+
+    fn num_suit(&self, suit: Suit) -> usize {
+        (self.mask & SPADES << suit as u32).count_ones() as usize
+    }
+
+    fn num_rank_height(&self, height: &RankHeight) -> usize {
+        (self.mask & get_rank_cards(height.mask())).count_ones() as usize
+    }
+}
+
+// The ranks of which a board mask holds at least one card
+fn get_rank_mask(mask: u64) -> u16 {
+    let any_suit = (mask | mask >> 1 | mask >> 2 | mask >> 3) & SPADES;
+
+    Rank::ALL.iter().fold(0, |ranks, rank| {
+        let bit = any_suit >> (4 * *rank as u32) & 1;
+        ranks | (bit as u16) << *rank as u32
+    })
 }
 
+// Every card of the ranks of a rank mask
+fn get_rank_cards(ranks: u16) -> u64 {
+    Rank::ALL
+        .iter()
+        .filter(|rank| ranks & rank.mask() != 0)
+        .fold(0, |mask, rank| mask | RANK_CARDS << (4 * *rank as u32))
+}
+
+// The flop from the highest card down, then the turn and the river
 impl Display for Board {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let s: String = self
-            .cards
-            .iter()
+            .get_flop()
+            .cards()
             .rev()
+            .chain(self.turn)
+            .chain(self.river)
             .map(|card| card.to_string())
             .collect();
 
@@ -59,10 +160,45 @@ impl Display for Board {
     }
 }
 
+// Iterates over the set bits of a board mask
+#[derive(Clone, Debug)]
+pub struct Cards(u64);
+
+impl Iterator for Cards {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let index = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(Card::from_index(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Cards {
+    fn next_back(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let index = 63 - self.0.leading_zeros() as usize;
+        self.0 &= !(1 << index);
+        Some(Card::from_index(index))
+    }
+}
+
+impl ExactSizeIterator for Cards {}
+
 #[cfg(test)]
 mod tests {
-    use crate::poker::{rank::Rank, suit::Suit};
-
     use super::*;
 
     #[test]
@@ -70,27 +206,93 @@ mod tests {
         let board = Board::try_from("3cAh7d").unwrap();
 
         assert_eq!(
-            board.cards,
-            BTreeSet::from([
-                Card::try_from("7d").unwrap(),
+            board.cards().collect::<Vec<_>>(),
+            vec![
                 Card::try_from("3c").unwrap(),
+                Card::try_from("7d").unwrap(),
                 Card::try_from("Ah").unwrap(),
-            ])
+            ]
         );
 
-        let mut iter = board.cards.iter();
+        let mut iter = board.cards();
         let card1 = iter.next().unwrap();
         let card2 = iter.next().unwrap();
         let card3 = iter.next().unwrap();
 
-        assert_eq!(card1.rank, Rank::_3);
-        assert_eq!(card1.suit, Suit::Club);
+        assert_eq!(card1.rank(), Rank::_3);
+        assert_eq!(card1.suit(), Suit::Club);
+
+        assert_eq!(card2.rank(), Rank::_7);
+        assert_eq!(card2.suit(), Suit::Diamond);
+
+        assert_eq!(card3.rank(), Rank::A);
+        assert_eq!(card3.suit(), Suit::Heart);
+    }
+
+    #[test]
+    fn test_cards() {
+        let board = Board::try_from("2hKs4c7dTd").unwrap();
+        let cards = board.cards();
+
+        assert_eq!(cards.len(), 5);
+        assert_eq!(cards.clone().next(), Card::try_from("2h").ok());
+        assert_eq!(cards.clone().next_back(), Card::try_from("Ks").ok());
+        assert_eq!(
+            cards.rev().map(|card| card.to_string()).collect::<String>(),
+            "KsTd7d4c2h"
+        );
+    }
+
+    #[test]
+    fn test_rank_mask_and_counts() {
+        let board = Board::try_from("KhKs8h2h").unwrap();
 
-        assert_eq!(card2.rank, Rank::_7);
-        assert_eq!(card2.suit, Suit::Diamond);
+        assert_eq!(
+            board.get_rank_mask(),
+            Rank::K.mask() | Rank::_8.mask() | Rank::_2.mask()
+        );
+
+        assert_eq!(board.num_rank(Rank::K), 2);
+        assert_eq!(board.num_rank(Rank::A), 0);
+        assert_eq!(board.num_suit(Suit::Heart), 3);
+        assert_eq!(board.num_suit(Suit::Diamond), 0);
+        assert_eq!(get_rank_cards(Rank::K.mask()).count_ones(), 4);
+    }
+
+    #[test]
+    fn test_board_try_from_turn_and_river() {
+        let turn = Board::try_from("Ks7d4c2h").unwrap();
+        assert_eq!(turn.num_cards(), 4);
+        assert_eq!(turn.to_string(), "Ks7d4c2h");
 
-        assert_eq!(card3.rank, Rank::A);
-        assert_eq!(card3.suit, Suit::Heart);
+        let river = Board::try_from("2hKs4c7dTd").unwrap();
+        assert_eq!(river.num_cards(), 5);
+        assert_eq!(river.to_string(), "Ks4c2h7dTd");
+
+        let turn = Board::try_from("KsQs2dAh").unwrap();
+        assert_eq!(turn.to_string(), "KsQs2dAh");
+    }
+
+    #[test]
+    fn test_board_streets() {
+        let flop = Board::try_from("Ks7d4c").unwrap();
+        assert_eq!(flop.turn, None);
+        assert_eq!(flop.river, None);
+        assert_eq!(flop.get_flop(), flop);
+
+        let river = Board::try_from("2hKs4c7dTd").unwrap();
+        assert_eq!(river.turn, Card::try_from("7d").ok());
+        assert_eq!(river.river, Card::try_from("Td").ok());
+        assert_eq!(river.get_flop(), Board::try_from("Ks4c2h").unwrap());
+    }
+
+    #[test]
+    fn test_invalid_board_try_from_num_cards() {
+        assert!(Board::try_from("Ks7d").is_err());
+        assert!(Board::try_from("Ks7d4c2h3h9s").is_err());
+        assert!(Board::try_from("Ks7d4c2").is_err());
+        assert!(Board::try_from("Ks7d4cKs").is_err());
+        assert!(Board::try_from("Ks7d4c2hKs").is_err());
     }
 
     #[test]
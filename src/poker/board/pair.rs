@@ -1,14 +1,28 @@
-use std::{collections::HashSet, str::FromStr};
+use std::str::FromStr;
 
-use crate::poker::ParseError;
+use crate::poker::{rank::Rank, ParseError};
 
 use super::Board;
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum BoardPair {
     Unpaired,
     Paired,
+    DoublePaired,
     Trips,
+    FullHouse,
+    Quads,
+}
+
+impl BoardPair {
+    pub const ALL: [BoardPair; 6] = [
+        BoardPair::Unpaired,
+        BoardPair::Paired,
+        BoardPair::DoublePaired,
+        BoardPair::Trips,
+        BoardPair::FullHouse,
+        BoardPair::Quads,
+    ];
 }
 
 impl FromStr for BoardPair {
@@ -18,39 +32,109 @@ impl FromStr for BoardPair {
         match s {
             "U" => Ok(BoardPair::Unpaired),
             "P" => Ok(BoardPair::Paired),
+            "DP" => Ok(BoardPair::DoublePaired),
             "T" => Ok(BoardPair::Trips),
+            "FH" => Ok(BoardPair::FullHouse),
+            "Q" => Ok(BoardPair::Quads),
             _ => Err(ParseError::str("pair", s)),
         }
     }
 }
 
+// Where the pair of a paired board sits relative to the unpaired cards, e.g. KK4 high and 44K low
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PairPosition {
+    High,
+    Middle,
+    Low,
+}
+
+impl FromStr for PairPosition {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<PairPosition, ParseError> {
+        match s {
+            "HIGH" => Ok(PairPosition::High),
+            "MID" => Ok(PairPosition::Middle),
+            "LOW" => Ok(PairPosition::Low),
+            _ => Err(ParseError::str("pair position", s)),
+        }
+    }
+}
+
 impl Board {
     pub fn is_unpaired(&self) -> bool {
-        self.num_unique_ranks() == 3
+        self.num_unique_ranks() == self.num_cards()
     }
 
     pub fn is_paired(&self) -> bool {
-        self.num_unique_ranks() == 2
+        matches!(self.get_rank_multiplicities()[2..], [1, 0, 0])
+    }
+
+    pub fn is_double_paired(&self) -> bool {
+        matches!(self.get_rank_multiplicities()[2..], [2, 0, 0])
     }
 
     pub fn is_trips(&self) -> bool {
-        self.num_unique_ranks() == 1
+        matches!(self.get_rank_multiplicities()[2..], [0, 1, 0])
+    }
+
+    pub fn is_full_house(&self) -> bool {
+        matches!(self.get_rank_multiplicities()[2..], [1, 1, 0])
+    }
+
+    pub fn is_quads(&self) -> bool {
+        matches!(self.get_rank_multiplicities()[2..], [_, _, 1])
     }
 
     pub fn is_pair(&self, pair: &BoardPair) -> bool {
         match pair {
             BoardPair::Unpaired => self.is_unpaired(),
             BoardPair::Paired => self.is_paired(),
+            BoardPair::DoublePaired => self.is_double_paired(),
             BoardPair::Trips => self.is_trips(),
+            BoardPair::FullHouse => self.is_full_house(),
+            BoardPair::Quads => self.is_quads(),
+        }
+    }
+
+    // None unless the board is paired, double paired boards and trips have no pair position
+    pub fn get_pair_position(&self) -> Option<PairPosition> {
+        if !self.is_paired() {
+            return None;
+        }
+
+        let pair = Rank::ALL
+            .into_iter()
+            .find(|rank| self.num_rank(*rank) == 2)?
+            .mask();
+        let kickers = self.get_rank_mask() & !pair;
+
+        if kickers < pair {
+            Some(PairPosition::High)
+        } else if kickers & (pair - 1) == 0 {
+            Some(PairPosition::Low)
+        } else {
+            Some(PairPosition::Middle)
         }
     }
 
+    pub fn is_pair_position(&self, position: &PairPosition) -> bool {
+        self.get_pair_position().as_ref() == Some(position)
+    }
+
     fn num_unique_ranks(&self) -> usize {
-        self.cards
-            .iter()
-            .map(|card| &card.rank)
-            .collect::<HashSet<_>>()
-            .len()
+        self.get_rank_mask().count_ones() as usize
+    }
+
+    // Number of ranks with no, one, two, three and four cards on the board
+    fn get_rank_multiplicities(&self) -> [usize; 5] {
+        let mut multiplicities = [0; 5];
+        for rank in Rank::ALL {
+            multiplicities[self.num_rank(rank)] += 1;
+        }
+
+        multiplicities
     }
 }
 
@@ -123,4 +207,68 @@ mod tests {
         assert_eq!(Board::try_from("2c8c8d").unwrap().num_unique_ranks(), 2);
         assert_eq!(Board::try_from("2cKd8h").unwrap().num_unique_ranks(), 3);
     }
+
+    #[test]
+    fn test_turn_and_river_pairs() {
+        assert!(Board::try_from("Ks7d4c2h").unwrap().is_unpaired());
+        assert!(Board::try_from("Ks7d4c7h").unwrap().is_paired());
+        assert!(Board::try_from("Ks7d4c7h2c").unwrap().is_paired());
+        assert!(Board::try_from("Ks7d4c7h4d").unwrap().is_double_paired());
+        assert!(Board::try_from("Ks7d4cKh4d").unwrap().is_double_paired());
+        assert!(Board::try_from("KsKd4cKh").unwrap().is_trips());
+        assert!(Board::try_from("KsKd4cKh2c").unwrap().is_trips());
+        assert!(Board::try_from("KsKd4cKh4h").unwrap().is_full_house());
+        assert!(Board::try_from("KsKdKcKh").unwrap().is_quads());
+        assert!(Board::try_from("KsKdKcKh2c").unwrap().is_quads());
+
+        assert!(!Board::try_from("Ks7d4c7h4d").unwrap().is_paired());
+        assert!(!Board::try_from("KsKd4cKh4h").unwrap().is_trips());
+        assert!(!Board::try_from("KsKdKcKh").unwrap().is_trips());
+    }
+
+    #[test]
+    fn test_board_pair_from_str() {
+        assert!(BoardPair::from_str("DP").unwrap() == BoardPair::DoublePaired);
+        assert!(BoardPair::from_str("FH").unwrap() == BoardPair::FullHouse);
+        assert!(BoardPair::from_str("Q").unwrap() == BoardPair::Quads);
+        assert!(BoardPair::from_str("X").is_err());
+    }
+
+    #[test]
+    fn test_get_pair_position() {
+        let position = |s: &str| Board::try_from(s).unwrap().get_pair_position();
+
+        assert_eq!(position("KsKd4c"), Some(PairPosition::High));
+        assert_eq!(position("4s4dKc"), Some(PairPosition::Low));
+        assert_eq!(position("Ks8d8c2h"), Some(PairPosition::Middle));
+        assert_eq!(position("Ks8d8c2hAh"), Some(PairPosition::Middle));
+        assert_eq!(position("Ks7d2c"), None);
+        assert_eq!(position("KsKdKc"), None);
+        assert_eq!(position("KsKd4c4h"), None);
+
+        assert!(Board::try_from("QsQd9c")
+            .unwrap()
+            .is_pair_position(&PairPosition::from_str("HIGH").unwrap()));
+        assert!(PairPosition::from_str("high").is_err());
+    }
+
+    #[test]
+    fn test_get_rank_multiplicities() {
+        assert_eq!(
+            Board::try_from("2c2d2h").unwrap().get_rank_multiplicities(),
+            [12, 0, 0, 1, 0]
+        );
+        assert_eq!(
+            Board::try_from("2c8c8d2h")
+                .unwrap()
+                .get_rank_multiplicities(),
+            [11, 0, 2, 0, 0]
+        );
+        assert_eq!(
+            Board::try_from("2cKd8h8c3h")
+                .unwrap()
+                .get_rank_multiplicities(),
+            [9, 3, 1, 0, 0]
+        );
+    }
 }
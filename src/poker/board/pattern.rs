@@ -0,0 +1,241 @@
+use std::str::FromStr;
+
+use crate::poker::{card::Card, rank::Rank, suit::Suit, ParseError};
+
+use super::Board;
+
+/*
+ * A board shape as coaches write it, one slot per card, e.g. Axx, KQx, T98 or A[KQJ]x.
+ * A slot is a rank, x for any rank or a class of ranks in brackets.
+ * Suits follow after a colon with one letter per slot: slots with the same letter
+ * share a suit, different letters mean different suits and x is any suit.
+ * So Kxx:ssy is a two-tone board with the K suited and :ssx, short for xxx:ssx,
+ * has two cards of a suit.
+ * Slots match the cards in any order, Axx matches 2d7cAs as well as AsAd7c.
+ * Case does not matter, kxx:SSY is the same pattern as Kxx:ssy.
+ */
+#[derive(Debug, PartialEq)]
+pub struct BoardPattern {
+    slots: Vec<Slot>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Slot {
+    // Rank mask of the ranks the card may have
+    ranks: u16,
+    suit: Option<char>,
+}
+
+const ANY: char = 'x';
+const ALL_RANKS: u16 = (1 << Rank::ALL.len()) - 1;
+
+impl FromStr for BoardPattern {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<BoardPattern, ParseError> {
+        let err = || ParseError::str("board pattern", s);
+
+        let (ranks, suits) = s.split_once(':').unwrap_or((s, ""));
+        let (ranks, suits) = (ranks.to_uppercase(), suits.to_lowercase());
+        let mut slots = Vec::new();
+        let mut chars = ranks.chars();
+
+        while let Some(c) = chars.next() {
+            let ranks = match c {
+                'X' => ALL_RANKS,
+                '[' => {
+                    let mut closed = false;
+                    let class: String = chars
+                        .by_ref()
+                        .take_while(|c| {
+                            closed = *c == ']';
+                            !closed
+                        })
+                        .collect();
+                    if !closed {
+                        return Err(err());
+                    }
+
+                    class
+                        .chars()
+                        .map(|c| Rank::try_from(c).map(|rank| rank.mask()))
+                        .try_fold(0, |ranks, rank| Ok::<_, ParseError>(ranks | rank?))
+                        .map_err(|_| err())?
+                }
+                _ => Rank::try_from(c).map_err(|_| err())?.mask(),
+            };
+
+            // An empty class leaves no rank
+            if ranks == 0 {
+                return Err(err());
+            }
+
+            slots.push(Slot { ranks, suit: None });
+        }
+
+        // Suits alone match any ranks
+        if slots.is_empty() {
+            slots = suits
+                .chars()
+                .map(|_| Slot {
+                    ranks: ALL_RANKS,
+                    suit: None,
+                })
+                .collect();
+        }
+
+        if !suits.is_empty() {
+            if suits.chars().count() != slots.len()
+                || !suits.chars().all(|c| c.is_ascii_lowercase())
+            {
+                return Err(err());
+            }
+
+            for (slot, suit) in slots.iter_mut().zip(suits.chars()) {
+                slot.suit = (suit != ANY).then_some(suit);
+            }
+        }
+
+        if slots.is_empty() {
+            return Err(err());
+        }
+
+        Ok(BoardPattern { slots })
+    }
+}
+
+impl Board {
+    // Boards only match patterns with one slot per card
+    pub fn matches_pattern(&self, pattern: &BoardPattern) -> bool {
+        let cards: Vec<Card> = self.cards().collect();
+        cards.len() == pattern.slots.len()
+            && match_slots(&pattern.slots, &cards, 0, &mut Vec::new())
+    }
+}
+
+/*
+ * Tries every card not used yet for the first slot and matches the other slots
+ * with the rest, binding each suit letter to the suit of the first card it is given.
+ */
+fn match_slots(slots: &[Slot], cards: &[Card], used: u8, suits: &mut Vec<(char, Suit)>) -> bool {
+    let Some((slot, rest)) = slots.split_first() else {
+        return true;
+    };
+
+    for (i, card) in cards.iter().enumerate() {
+        if used & 1 << i != 0 || slot.ranks & card.rank().mask() == 0 {
+            continue;
+        }
+
+        let mut bound = false;
+        if let Some(letter) = slot.suit {
+            match suits.iter().find(|(other, _)| *other == letter) {
+                Some((_, suit)) if *suit != card.suit() => continue,
+                Some(_) => {}
+                None if suits.iter().any(|(_, suit)| *suit == card.suit()) => continue,
+                None => {
+                    suits.push((letter, card.suit()));
+                    bound = true;
+                }
+            }
+        }
+
+        if match_slots(rest, cards, used | 1 << i, suits) {
+            return true;
+        }
+
+        if bound {
+            suits.pop();
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(board: &str, pattern: &str) -> bool {
+        Board::try_from(board)
+            .unwrap()
+            .matches_pattern(&BoardPattern::from_str(pattern).unwrap())
+    }
+
+    #[test]
+    fn test_board_pattern_from_str() {
+        let pattern = BoardPattern::from_str("A[KQJ]x:ssx").unwrap();
+
+        assert_eq!(
+            pattern.slots,
+            vec![
+                Slot {
+                    ranks: Rank::A.mask(),
+                    suit: Some('s'),
+                },
+                Slot {
+                    ranks: Rank::K.mask() | Rank::Q.mask() | Rank::J.mask(),
+                    suit: Some('s'),
+                },
+                Slot {
+                    ranks: ALL_RANKS,
+                    suit: None,
+                },
+            ]
+        );
+
+        assert!(BoardPattern::from_str("").is_err());
+        assert!(BoardPattern::from_str(":").is_err());
+        assert!(BoardPattern::from_str("A1x").is_err());
+        assert!(BoardPattern::from_str("A[KQx").is_err());
+        assert!(BoardPattern::from_str("A[KQ").is_err());
+        assert!(BoardPattern::from_str("A[]x").is_err());
+        assert!(BoardPattern::from_str("Axx:ss").is_err());
+        assert!(BoardPattern::from_str("A1x:ss").is_err());
+
+        assert_eq!(
+            BoardPattern::from_str("a[kqj]X:SSX").unwrap(),
+            BoardPattern::from_str("A[KQJ]x:ssx").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_matches_ranks() {
+        assert!(matches("As7d2c", "Axx"));
+        assert!(matches("2c7dAs", "xxA"));
+        assert!(matches("AsAd2c", "Axx"));
+        assert!(!matches("Ks7d2c", "Axx"));
+
+        assert!(matches("KsQd2c", "KQx"));
+        assert!(matches("Ts9d8c", "T98"));
+        assert!(!matches("Ts9d7c", "T98"));
+        assert!(matches("Ks7d2c", "xx2"));
+
+        assert!(matches("AsJd4c", "A[KQJ]x"));
+        assert!(!matches("AsTd4c", "A[KQJ]x"));
+        assert!(matches("AsKdQc", "A[KQJ][KQJ]"));
+        assert!(!matches("AsKd2c", "A[KQJ][KQJ]"));
+    }
+
+    #[test]
+    fn test_matches_suits() {
+        assert!(matches("Ks7s2c", "Kxx:ssy"));
+        assert!(matches("Ks7s2c", "kxx:SSY"));
+        assert!(matches("Ks7c2c", "Kxx:xss"));
+        assert!(!matches("Ks7c2c", "Kxx:ssy"));
+        assert!(!matches("Ks7s2s", "Kxx:ssy"));
+
+        assert!(matches("Ks7s2c", "xxx:ssx"));
+        assert!(matches("Ks7s2s", "xxx:ssx"));
+        assert!(!matches("Ks7d2c", "xxx:ssx"));
+        assert!(matches("Ks7d2c", "xxx:abc"));
+        assert!(matches("Ks7s2c", ":ssx"));
+        assert!(!matches("Ks7d2c", ":ssx"));
+    }
+
+    #[test]
+    fn test_matches_num_cards() {
+        assert!(!matches("As7d2c9h", "Axx"));
+        assert!(matches("As7d2c9h", "Axxx"));
+    }
+}
@@ -1,14 +1,22 @@
-use std::collections::HashSet;
-
-use crate::poker::ParseError;
+use crate::poker::{suit::Suit, ParseError};
 
 use super::Board;
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum BoardSuit {
     Rainbow,
     Twotone,
     Montone,
+    FourFlush,
+}
+
+impl BoardSuit {
+    pub const ALL: [BoardSuit; 4] = [
+        BoardSuit::Rainbow,
+        BoardSuit::Twotone,
+        BoardSuit::Montone,
+        BoardSuit::FourFlush,
+    ];
 }
 
 impl TryFrom<&str> for BoardSuit {
@@ -19,22 +27,31 @@ impl TryFrom<&str> for BoardSuit {
             "R" => Ok(BoardSuit::Rainbow),
             "T" => Ok(BoardSuit::Twotone),
             "M" => Ok(BoardSuit::Montone),
+            "F" => Ok(BoardSuit::FourFlush),
             _ => Err(ParseError::str("suit", s)),
         }
     }
 }
 
+/*
+ * Classified by the most cards of one suit, so on the turn and river
+ * twotone means at most a flush draw and monotone three to a flush
+ */
 impl Board {
     pub fn is_rainbow(&self) -> bool {
-        self.num_unique_suits() == 3
+        self.get_max_suit_count() == 1
     }
 
     pub fn is_twotone(&self) -> bool {
-        self.num_unique_suits() == 2
+        self.get_max_suit_count() == 2
     }
 
     pub fn is_monotone(&self) -> bool {
-        self.num_unique_suits() == 1
+        self.get_max_suit_count() == 3
+    }
+
+    pub fn is_four_flush(&self) -> bool {
+        self.get_max_suit_count() >= 4
     }
 
     pub fn is_suit(&self, suit: &BoardSuit) -> bool {
@@ -42,15 +59,16 @@ impl Board {
             BoardSuit::Rainbow => self.is_rainbow(),
             BoardSuit::Twotone => self.is_twotone(),
             BoardSuit::Montone => self.is_monotone(),
+            BoardSuit::FourFlush => self.is_four_flush(),
         }
     }
 
-    fn num_unique_suits(&self) -> usize {
-        self.cards
-            .iter()
-            .map(|card| &card.suit)
-            .collect::<HashSet<_>>()
-            .len()
+    fn get_max_suit_count(&self) -> usize {
+        Suit::ALL
+            .into_iter()
+            .map(|suit| self.num_suit(suit))
+            .max()
+            .unwrap_or_default()
     }
 }
 
@@ -99,9 +117,25 @@ mod tests {
     }
 
     #[test]
-    fn test_num_unique_suits() {
-        assert_eq!(Board::try_from("JcTc7c").unwrap().num_unique_suits(), 1);
-        assert_eq!(Board::try_from("JcTh7h").unwrap().num_unique_suits(), 2);
-        assert_eq!(Board::try_from("JcTs7h").unwrap().num_unique_suits(), 3);
+    fn test_get_max_suit_count() {
+        assert_eq!(Board::try_from("JcTc7c").unwrap().get_max_suit_count(), 3);
+        assert_eq!(Board::try_from("JcTh7h").unwrap().get_max_suit_count(), 2);
+        assert_eq!(Board::try_from("JcTs7h").unwrap().get_max_suit_count(), 1);
+        assert_eq!(Board::try_from("JcTs7h2c").unwrap().get_max_suit_count(), 2);
+        assert_eq!(
+            Board::try_from("JcTc7h2c5c").unwrap().get_max_suit_count(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_turn_and_river_suits() {
+        assert!(Board::try_from("Ks7d4c2h").unwrap().is_rainbow());
+        assert!(Board::try_from("Ks7s4c2c").unwrap().is_twotone());
+        assert!(Board::try_from("Ks7s4c2h").unwrap().is_twotone());
+        assert!(Board::try_from("Ks7s4s2h").unwrap().is_monotone());
+        assert!(Board::try_from("Ks7s4s2s").unwrap().is_four_flush());
+        assert!(Board::try_from("Ks7s4s2s9s").unwrap().is_four_flush());
+        assert!(!Board::try_from("Ks7s4s2s").unwrap().is_monotone());
     }
 }
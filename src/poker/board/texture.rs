@@ -0,0 +1,158 @@
+use std::sync::OnceLock;
+
+use super::{
+    connection::Connection,
+    enumeration::{self, NUM_FLOPS},
+    height::BoardHeight,
+    pair::BoardPair,
+    suit::BoardSuit,
+    Board,
+};
+
+const HEIGHT_OFFSET: u32 = 0;
+const SUIT_OFFSET: u32 = HEIGHT_OFFSET + BoardHeight::ALL.len() as u32;
+const PAIR_OFFSET: u32 = SUIT_OFFSET + BoardSuit::ALL.len() as u32;
+const CONNECTION_OFFSET: u32 = PAIR_OFFSET + BoardPair::ALL.len() as u32;
+
+/*
+ * The height, suit, pair and connection classes of a board with one bit per class.
+ * Flops are looked up in a table of all 22,100 flops that is built on first use,
+ * turns and rivers are classified on the spot.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Texture(u32);
+
+static FLOP_TEXTURES: OnceLock<Vec<Texture>> = OnceLock::new();
+
+impl Texture {
+    pub fn of(board: &Board) -> Texture {
+        if board.num_cards() != 3 {
+            return Texture::classify(board);
+        }
+
+        FLOP_TEXTURES.get_or_init(build_flop_textures)[get_flop_index(board)]
+    }
+
+    pub fn is_height(&self, height: &BoardHeight) -> bool {
+        self.has(HEIGHT_OFFSET + *height as u32)
+    }
+
+    pub fn is_suit(&self, suit: &BoardSuit) -> bool {
+        self.has(SUIT_OFFSET + *suit as u32)
+    }
+
+    pub fn is_pair(&self, pair: &BoardPair) -> bool {
+        self.has(PAIR_OFFSET + *pair as u32)
+    }
+
+    pub fn is_connection(&self, connection: &Connection) -> bool {
+        self.has(CONNECTION_OFFSET + *connection as u32)
+    }
+
+    fn classify(board: &Board) -> Texture {
+        let heights = BoardHeight::ALL
+            .iter()
+            .filter(|height| board.is_height(height))
+            .map(|height| HEIGHT_OFFSET + *height as u32);
+        let suits = BoardSuit::ALL
+            .iter()
+            .filter(|suit| board.is_suit(suit))
+            .map(|suit| SUIT_OFFSET + *suit as u32);
+        let pairs = BoardPair::ALL
+            .iter()
+            .filter(|pair| board.is_pair(pair))
+            .map(|pair| PAIR_OFFSET + *pair as u32);
+        let connectivity = board.get_connectivity();
+        let connections = Connection::ALL
+            .iter()
+            .filter(|connection| board.is_connection_with(connection, &connectivity))
+            .map(|connection| CONNECTION_OFFSET + *connection as u32);
+
+        Texture(
+            heights
+                .chain(suits)
+                .chain(pairs)
+                .chain(connections)
+                .fold(0, |bits, bit| bits | 1 << bit),
+        )
+    }
+
+    fn has(&self, bit: u32) -> bool {
+        self.0 >> bit & 1 != 0
+    }
+}
+
+fn build_flop_textures() -> Vec<Texture> {
+    let mut textures = vec![Texture::default(); NUM_FLOPS];
+    for flop in enumeration::get_all_flops() {
+        textures[get_flop_index(&flop)] = Texture::classify(&flop);
+    }
+
+    textures
+}
+
+/*
+ * Position of a flop among all flops in the combinatorial number system:
+ * cards c1 < c2 < c3 of the deck map to C(c1, 1) + C(c2, 2) + C(c3, 3)
+ */
+fn get_flop_index(flop: &Board) -> usize {
+    flop.cards()
+        .zip(1..)
+        .map(|(card, k)| get_binomial(card.index(), k))
+        .sum()
+}
+
+fn get_binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+
+    (0..k).fold(1, |binomial, i| binomial * (n - i) / (i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_get_flop_index() {
+        let indices: HashSet<usize> = enumeration::get_all_flops()
+            .iter()
+            .map(get_flop_index)
+            .collect();
+
+        assert_eq!(indices.len(), NUM_FLOPS);
+        assert!(indices.iter().all(|index| *index < NUM_FLOPS));
+        assert_eq!(get_flop_index(&Board::try_from("2d2c2s").unwrap()), 0);
+        assert_eq!(
+            get_flop_index(&Board::try_from("AhAdAc").unwrap()),
+            NUM_FLOPS - 1
+        );
+    }
+
+    #[test]
+    fn test_flop_textures_match_classifiers() {
+        for flop in enumeration::get_all_flops() {
+            assert_eq!(Texture::of(&flop), Texture::classify(&flop), "{flop}");
+        }
+
+        let texture = Texture::of(&Board::try_from("KsQs2d").unwrap());
+        assert!(texture.is_height(&BoardHeight::DoubleBW));
+        assert!(texture.is_suit(&BoardSuit::Twotone));
+        assert!(texture.is_pair(&BoardPair::Unpaired));
+        assert!(texture.is_connection(&Connection::Oesd));
+        assert!(!texture.is_suit(&BoardSuit::Rainbow));
+        assert!(!texture.is_pair(&BoardPair::Paired));
+    }
+
+    #[test]
+    fn test_texture_of_turn() {
+        let texture = Texture::of(&Board::try_from("Ks7d7c7h").unwrap());
+
+        assert!(texture.is_pair(&BoardPair::Trips));
+        assert!(texture.is_height(&BoardHeight::SingleBW));
+        assert!(texture.is_suit(&BoardSuit::Rainbow));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::poker::{card::Card, ParseError};
+
+use super::{Board, STRAIGHTS};
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum TurnCard {
+    Overcard,
+    Undercard,
+    BoardPairing,
+    TripsMaking,
+    FlushCompleting,
+    BackdoorFlush,
+    StraightCompleting,
+    Blank,
+}
+
+impl TryFrom<&str> for TurnCard {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<TurnCard, ParseError> {
+        match s {
+            "O" => Ok(TurnCard::Overcard),
+            "U" => Ok(TurnCard::Undercard),
+            "P" => Ok(TurnCard::BoardPairing),
+            "T" => Ok(TurnCard::TripsMaking),
+            "FL" => Ok(TurnCard::FlushCompleting),
+            "BDF" => Ok(TurnCard::BackdoorFlush),
+            "ST" => Ok(TurnCard::StraightCompleting),
+            "B" => Ok(TurnCard::Blank),
+            _ => Err(ParseError::str("turn card", s)),
+        }
+    }
+}
+
+/*
+ * The turn card is classified relative to the flop it was dealt on.
+ * A turn can fall into several classes (e.g. an overcard completing a flush),
+ * a blank is one that falls into none of them except undercard.
+ * Flops have no turn card and match no class.
+ */
+impl Board {
+    pub fn is_overcard_turn(&self) -> bool {
+        self.with_turn(|flop, turn| flop.get_rank_mask() < turn.rank().mask())
+    }
+
+    pub fn is_undercard_turn(&self) -> bool {
+        self.with_turn(|flop, turn| flop.get_rank_mask() & ((turn.rank().mask() << 1) - 1) == 0)
+    }
+
+    pub fn is_board_pairing_turn(&self) -> bool {
+        self.with_turn(|flop, turn| flop.num_rank(turn.rank()) == 1)
+    }
+
+    pub fn is_trips_making_turn(&self) -> bool {
+        self.with_turn(|flop, turn| flop.num_rank(turn.rank()) == 2)
+    }
+
+    // Third card of a suit, so two suited hole cards make a flush
+    pub fn is_flush_completing_turn(&self) -> bool {
+        self.with_turn(|flop, turn| flop.num_suit(turn.suit()) == 2)
+    }
+
+    // Second card of a suit that only had a backdoor draw on the flop
+    pub fn is_backdoor_flush_turn(&self) -> bool {
+        self.with_turn(|flop, turn| flop.num_suit(turn.suit()) == 1)
+    }
+
+    // Part of a straight with at least three different ranks of flop and turn
+    pub fn is_straight_completing_turn(&self) -> bool {
+        self.with_turn(|flop, turn| {
+            let ranks = flop.get_rank_mask();
+            let turn_rank = turn.rank().mask();
+            if ranks & turn_rank != 0 {
+                return false;
+            }
+
+            STRAIGHTS
+                .iter()
+                .filter(|straight| *straight & turn_rank != 0)
+                .any(|straight| (straight & ranks).count_ones() + 1 >= 3)
+        })
+    }
+
+    pub fn is_blank_turn(&self) -> bool {
+        self.turn.is_some()
+            && !self.is_overcard_turn()
+            && !self.is_board_pairing_turn()
+            && !self.is_trips_making_turn()
+            && !self.is_flush_completing_turn()
+            && !self.is_backdoor_flush_turn()
+            && !self.is_straight_completing_turn()
+    }
+
+    pub fn is_turn_card(&self, turn_card: &TurnCard) -> bool {
+        match turn_card {
+            TurnCard::Overcard => self.is_overcard_turn(),
+            TurnCard::Undercard => self.is_undercard_turn(),
+            TurnCard::BoardPairing => self.is_board_pairing_turn(),
+            TurnCard::TripsMaking => self.is_trips_making_turn(),
+            TurnCard::FlushCompleting => self.is_flush_completing_turn(),
+            TurnCard::BackdoorFlush => self.is_backdoor_flush_turn(),
+            TurnCard::StraightCompleting => self.is_straight_completing_turn(),
+            TurnCard::Blank => self.is_blank_turn(),
+        }
+    }
+
+    fn with_turn(&self, classify: impl Fn(&Board, Card) -> bool) -> bool {
+        self.turn
+            .is_some_and(|turn| classify(&self.get_flop(), turn))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(s: &str) -> Board {
+        Board::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn test_turn_card_try_from() {
+        assert_eq!(TurnCard::try_from("O").unwrap(), TurnCard::Overcard);
+        assert_eq!(TurnCard::try_from("BDF").unwrap(), TurnCard::BackdoorFlush);
+        assert!(TurnCard::try_from("X").is_err());
+    }
+
+    #[test]
+    fn test_flop_has_no_turn_card() {
+        let flop = board("Ks7d2c");
+        assert!(!flop.is_overcard_turn());
+        assert!(!flop.is_undercard_turn());
+        assert!(!flop.is_blank_turn());
+    }
+
+    #[test]
+    fn test_overcard_and_undercard_turn() {
+        assert!(board("Ts7d2cAh").is_overcard_turn());
+        assert!(!board("Ts7d2c9h").is_overcard_turn());
+        assert!(!board("Ts7d2cTh").is_overcard_turn());
+
+        assert!(board("Ts7d3c2h").is_undercard_turn());
+        assert!(!board("Ts7d3c4h").is_undercard_turn());
+    }
+
+    #[test]
+    fn test_pairing_turn() {
+        assert!(board("Ks7d2c7h").is_board_pairing_turn());
+        assert!(!board("Ks7d2c7h").is_trips_making_turn());
+
+        assert!(board("Ks7d7c7h").is_trips_making_turn());
+        assert!(!board("Ks7d7c7h").is_board_pairing_turn());
+
+        assert!(!board("Ks7d2c8h").is_board_pairing_turn());
+    }
+
+    #[test]
+    fn test_flush_turn() {
+        assert!(board("Kh7h2c4h").is_flush_completing_turn());
+        assert!(!board("Kh7h2c4c").is_flush_completing_turn());
+        assert!(board("Kh7h2c4c").is_backdoor_flush_turn());
+        assert!(!board("Kh7h2c4s").is_backdoor_flush_turn());
+        assert!(!board("Kh7h2c4s").is_flush_completing_turn());
+    }
+
+    #[test]
+    fn test_straight_completing_turn() {
+        assert!(board("Ks8d6c9h").is_straight_completing_turn());
+        assert!(board("QsJd2cTh").is_straight_completing_turn());
+        assert!(board("Ks4d2cAh").is_straight_completing_turn());
+        assert!(board("9s8d7c6h").is_straight_completing_turn());
+        assert!(!board("Ks8d2c3h").is_straight_completing_turn());
+        assert!(!board("9s8d2c9h").is_straight_completing_turn());
+    }
+
+    #[test]
+    fn test_blank_turn() {
+        assert!(board("Ks8d4s2c").is_blank_turn());
+        assert!(board("Ks8d4s2c").is_undercard_turn());
+        assert!(!board("Ks8d4cAs").is_blank_turn());
+        assert!(!board("Ks8d4c2c").is_blank_turn());
+        assert!(!board("Ks8d4c8h").is_blank_turn());
+    }
+
+    #[test]
+    fn test_turn_of_river_board() {
+        let river = board("Ks7d2cAh7s");
+        assert!(river.is_overcard_turn());
+        assert!(!river.is_board_pairing_turn());
+    }
+}
@@ -3,17 +3,17 @@ use std::{
     ops::Sub,
 };
 
-use super::{
-    rank::{Rank, RankHeight},
-    suit::Suit,
-    ParseError,
-};
+use super::{rank::Rank, suit::Suit, ParseError};
 
-#[derive(PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
-pub struct Card {
-    pub rank: Rank,
-    pub suit: Suit,
-}
+/*
+ * A card packed into its index in the deck, rank * 4 + suit.
+ * Cards are ordered by rank first and suit second, like the index.
+ */
+#[derive(PartialEq, Eq, Hash, Debug, PartialOrd, Ord, Clone, Copy)]
+pub struct Card(u8);
+
+pub const NUM_CARDS: usize = 52;
+const NUM_SUITS: u8 = 4;
 
 impl TryFrom<&str> for Card {
     type Error = ParseError;
@@ -38,52 +38,46 @@ impl TryFrom<(char, char)> for Card {
         let rank = Rank::try_from(rank)?;
         let suit = Suit::try_from(suit)?;
 
-        Ok(Card { rank, suit })
+        Ok(Card::new(rank, suit))
     }
 }
 
 impl Display for Card {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{}", self.rank, self.suit)
+        write!(f, "{}{}", self.rank(), self.suit())
     }
 }
 
 impl Card {
-    pub fn is_bw(&self) -> bool {
-        self.rank.is_bw()
+    pub fn new(rank: Rank, suit: Suit) -> Card {
+        Card(rank as u8 * NUM_SUITS + suit as u8)
     }
 
-    pub fn is_middling(&self) -> bool {
-        self.rank.is_middling()
+    // Panics for indices outside the deck
+    pub fn from_index(index: usize) -> Card {
+        assert!(index < NUM_CARDS, "card index {index} is outside the deck");
+        Card(index as u8)
     }
 
-    pub fn is_low(&self) -> bool {
-        self.rank.is_low()
+    pub fn index(&self) -> usize {
+        self.0 as usize
     }
 
-    pub fn is_wheel(&self) -> bool {
-        self.rank.is_wheel()
+    // The bit of this card in a board mask
+    pub fn mask(&self) -> u64 {
+        1 << self.0
     }
 
-    pub fn is_height(&self, height: &RankHeight) -> bool {
-        self.rank.is_height(height)
+    pub fn rank(&self) -> Rank {
+        Rank::ALL[(self.0 / NUM_SUITS) as usize]
     }
 
-    pub fn get_heights(&self) -> Vec<RankHeight> {
-        self.rank.get_heights()
+    pub fn suit(&self) -> Suit {
+        Suit::ALL[(self.0 % NUM_SUITS) as usize]
     }
 
-    pub fn is_ace(&self) -> bool {
-        self.rank == Rank::A
-    }
-
-    pub fn get_distances<'a>(cards: impl Iterator<Item = &'a Card>) -> Vec<i32> {
-        cards
-            .collect::<Vec<_>>()
-            .windows(2)
-            .map(|cards| cards[1] - cards[0])
-            .map(|diff| diff.abs())
-            .collect()
+    pub fn get_deck() -> Vec<Card> {
+        (0..NUM_CARDS).map(Card::from_index).collect()
     }
 }
 
@@ -91,15 +85,7 @@ impl Sub for Card {
     type Output = i32;
 
     fn sub(self, rhs: Card) -> i32 {
-        &self - &rhs
-    }
-}
-
-impl Sub for &Card {
-    type Output = i32;
-
-    fn sub(self, rhs: &Card) -> i32 {
-        self.rank - rhs.rank
+        self.rank() - rhs.rank()
     }
 }
 
@@ -107,119 +93,81 @@ impl Sub for &Card {
 mod tests {
     use std::cmp::Ordering;
 
+    use crate::poker::rank::RankHeight;
+
     use super::*;
 
     #[test]
     fn test_card_try_from_str() {
         let card = Card::try_from("As").unwrap();
-        assert_eq!(card.rank, Rank::A);
-        assert_eq!(card.suit, Suit::Spade);
+        assert_eq!(card.rank(), Rank::A);
+        assert_eq!(card.suit(), Suit::Spade);
     }
 
     #[test]
     fn test_card_try_from_chars() {
         let card = Card::try_from(('K', 'c')).unwrap();
-        assert_eq!(card.rank, Rank::K);
-        assert_eq!(card.suit, Suit::Club);
+        assert_eq!(card.rank(), Rank::K);
+        assert_eq!(card.suit(), Suit::Club);
     }
 
     #[test]
-    fn test_card_is_bw() {
-        assert!(Card::try_from("Ac").unwrap().is_bw());
-        assert!(Card::try_from("Qc").unwrap().is_bw());
-        assert!(!Card::try_from("9c").unwrap().is_bw());
-        assert!(!Card::try_from("7c").unwrap().is_bw());
-        assert!(!Card::try_from("5c").unwrap().is_bw());
-        assert!(!Card::try_from("2c").unwrap().is_bw());
-    }
-
-    #[test]
-    fn test_card_is_middling() {
-        assert!(!Card::try_from("Ac").unwrap().is_middling());
-        assert!(!Card::try_from("Qc").unwrap().is_middling());
-        assert!(Card::try_from("9c").unwrap().is_middling());
-        assert!(Card::try_from("7c").unwrap().is_middling());
-        assert!(!Card::try_from("5c").unwrap().is_middling());
-        assert!(!Card::try_from("2c").unwrap().is_middling());
-    }
-
-    #[test]
-    fn test_card_is_low() {
-        assert!(!Card::try_from("Ac").unwrap().is_low());
-        assert!(!Card::try_from("Qc").unwrap().is_low());
-        assert!(!Card::try_from("9c").unwrap().is_low());
-        assert!(!Card::try_from("7c").unwrap().is_low());
-        assert!(Card::try_from("5c").unwrap().is_low());
-        assert!(Card::try_from("2c").unwrap().is_low());
-    }
-
-    #[test]
-    fn test_card_is_wheel() {
-        assert!(Card::try_from("Ac").unwrap().is_wheel());
-        assert!(!Card::try_from("Qc").unwrap().is_wheel());
-        assert!(!Card::try_from("9c").unwrap().is_wheel());
-        assert!(!Card::try_from("7c").unwrap().is_wheel());
-        assert!(Card::try_from("5c").unwrap().is_wheel());
-        assert!(Card::try_from("2c").unwrap().is_wheel());
+    fn test_get_deck() {
+        let deck = Card::get_deck();
+        assert_eq!(deck.len(), 52);
+        assert!(deck.contains(&Card::try_from("As").unwrap()));
+        assert!(deck.contains(&Card::try_from("2h").unwrap()));
+        assert!(deck.windows(2).all(|cards| cards[0] < cards[1]));
     }
 
+    // The heights of a card are the ones of its rank
     #[test]
     fn test_card_is_height() {
-        assert!(Card::try_from("Ah")
-            .unwrap()
-            .is_height(&RankHeight::Broadway));
-        assert!(!Card::try_from("Ah")
-            .unwrap()
-            .is_height(&RankHeight::Middling));
-        assert!(!Card::try_from("Ah").unwrap().is_height(&RankHeight::Low));
-        assert!(Card::try_from("Ah").unwrap().is_height(&RankHeight::Wheel));
-
-        assert!(!Card::try_from("9c")
-            .unwrap()
-            .is_height(&RankHeight::Broadway));
-        assert!(Card::try_from("9h")
-            .unwrap()
-            .is_height(&RankHeight::Middling));
-        assert!(!Card::try_from("9h").unwrap().is_height(&RankHeight::Low));
-        assert!(!Card::try_from("9h").unwrap().is_height(&RankHeight::Wheel));
-
-        assert!(!Card::try_from("6c")
-            .unwrap()
-            .is_height(&RankHeight::Broadway));
-        assert!(!Card::try_from("6h")
-            .unwrap()
-            .is_height(&RankHeight::Middling));
-        assert!(Card::try_from("6h").unwrap().is_height(&RankHeight::Low));
-        assert!(!Card::try_from("6h").unwrap().is_height(&RankHeight::Wheel));
-
-        assert!(!Card::try_from("3c")
-            .unwrap()
-            .is_height(&RankHeight::Broadway));
-        assert!(!Card::try_from("3h")
-            .unwrap()
-            .is_height(&RankHeight::Middling));
-        assert!(Card::try_from("3h").unwrap().is_height(&RankHeight::Low));
-        assert!(Card::try_from("3h").unwrap().is_height(&RankHeight::Wheel));
-    }
-
-    #[test]
-    fn test_card_is_ace() {
-        assert!(Card::try_from("Ah").unwrap().is_ace());
-        assert!(!Card::try_from("9h").unwrap().is_ace());
-        assert!(!Card::try_from("6h").unwrap().is_ace());
-        assert!(!Card::try_from("2h").unwrap().is_ace());
-    }
-
-    #[test]
-    fn test_get_distances() {
+        let card = |s| Card::try_from(s).unwrap().rank();
+
+        assert!(card("Ac").is_bw());
+        assert!(card("Ac").is_wheel());
+        assert!(!card("Ac").is_middling());
+        assert!(card("Qc").is_bw());
+        assert!(!card("Qc").is_wheel());
+        assert!(card("9h").is_middling());
+        assert!(!card("9h").is_height(&RankHeight::Broadway));
+        assert!(card("7c").is_middling());
+        assert!(card("6h").is_low());
+        assert!(!card("6h").is_wheel());
+        assert!(card("3h").is_height(&RankHeight::Low));
+        assert!(card("3h").is_height(&RankHeight::Wheel));
+        assert!(!card("2c").is_bw());
+    }
+
+    // Rank distances of neighbouring cards, as Card::get_distances gave them
+    #[test]
+    fn test_card_distances() {
         let cards = [
             Card::try_from("Qc").unwrap(),
             Card::try_from("Th").unwrap(),
             Card::try_from("7c").unwrap(),
             Card::try_from(('7', 'h')).unwrap(),
         ];
+        let distances: Vec<i32> = cards
+            .windows(2)
+            .map(|cards| (cards[1] - cards[0]).abs())
+            .collect();
+
+        assert_eq!(distances, vec![2, 3, 0]);
+    }
 
-        assert_eq!(Card::get_distances(cards.iter()), vec![2, 3, 0]);
+    #[test]
+    fn test_card_index() {
+        assert_eq!(Card::try_from("2s").unwrap().index(), 0);
+        assert_eq!(Card::try_from("2h").unwrap().index(), 3);
+        assert_eq!(Card::try_from("Ah").unwrap().index(), 51);
+        assert_eq!(Card::try_from("Ac").unwrap().mask(), 1 << 49);
+
+        for (index, card) in Card::get_deck().iter().enumerate() {
+            assert_eq!(card.index(), index);
+            assert_eq!(Card::new(card.rank(), card.suit()), *card);
+        }
     }
 
     #[test]
@@ -278,7 +226,6 @@ mod tests {
     fn test_card_sub_1() {
         let card1 = Card::try_from("3h").unwrap();
         let card2 = Card::try_from("3c").unwrap();
-        assert_eq!(&card1 - &card2, 0);
         assert_eq!(card1 - card2, 0);
     }
 
@@ -286,7 +233,6 @@ mod tests {
     fn test_card_sub_2() {
         let card1 = Card::try_from("8c").unwrap();
         let card2 = Card::try_from("4c").unwrap();
-        assert_eq!(&card1 - &card2, 4);
         assert_eq!(card1 - card2, 4);
     }
 
@@ -294,7 +240,6 @@ mod tests {
     fn test_card_sub_3() {
         let card1 = Card::try_from("Th").unwrap();
         let card2 = Card::try_from("Ac").unwrap();
-        assert_eq!(&card1 - &card2, -4);
         assert_eq!(card1 - card2, -4);
     }
 
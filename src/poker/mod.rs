@@ -3,7 +3,7 @@
  * Rank = card without suit (e.g. A)
  * Suit = suit modifier after rank (s, c, d or h)
  * Card = rank + suit (e.g. As)
- * Board = list of cards (e.g. flop = 3 cards)
+ * Board = list of cards (flop = 3 cards, turn = 4 cards, river = 5 cards)
  */
 
 pub mod action;
@@ -27,7 +27,7 @@ impl ParseError {
         ParseError::str(obj, &src.to_string())
     }
 
-    fn str(obj: &str, src: &str) -> ParseError {
+    pub fn str(obj: &str, src: &str) -> ParseError {
         ParseError(format!("error parsing {obj} from {src}"))
     }
 }
@@ -12,7 +12,8 @@ const BTN_STR: &str = "BTN";
 const SB_STR: &str = "SB";
 const BB_STR: &str = "BB";
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+// Declared in preflop order
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Position {
     LJ,
     HJ,
@@ -53,7 +54,115 @@ impl Display for Position {
     }
 }
 
+impl Position {
+    fn postflop_order(&self) -> u8 {
+        match self {
+            Position::SB => 0,
+            Position::BB => 1,
+            Position::LJ => 2,
+            Position::HJ => 3,
+            Position::CO => 4,
+            Position::Btn => 5,
+        }
+    }
+}
+
+const POSITIONS_SEPARATOR: &str = " vs ";
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Positions {
     pub ip: Position,
     pub oop: Position,
 }
+
+impl Positions {
+    pub fn new(pos1: Position, pos2: Position) -> Result<Positions, ParseError> {
+        if pos1 == pos2 {
+            return Err(ParseError::str("positions", &format!("{pos1} {pos2}")));
+        }
+
+        if pos1.postflop_order() > pos2.postflop_order() {
+            Ok(Positions {
+                ip: pos1,
+                oop: pos2,
+            })
+        } else {
+            Ok(Positions {
+                ip: pos2,
+                oop: pos1,
+            })
+        }
+    }
+}
+
+impl FromStr for Positions {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Positions, ParseError> {
+        let (pos1, pos2) = s
+            .split_once(POSITIONS_SEPARATOR)
+            .ok_or_else(|| ParseError::str("positions", s))?;
+
+        let pos1 = Position::from_str(pos1).map_err(|_| ParseError::str("positions", s))?;
+        let pos2 = Position::from_str(pos2).map_err(|_| ParseError::str("positions", s))?;
+
+        Positions::new(pos1, pos2)
+    }
+}
+
+impl Display for Positions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (first, second) = if self.ip < self.oop {
+            (&self.ip, &self.oop)
+        } else {
+            (&self.oop, &self.ip)
+        };
+
+        write!(f, "{first}{POSITIONS_SEPARATOR}{second}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions_from_str() {
+        let positions = Positions::from_str("BTN vs BB").unwrap();
+        assert_eq!(positions.ip, Position::Btn);
+        assert_eq!(positions.oop, Position::BB);
+
+        let positions = Positions::from_str("BB vs BTN").unwrap();
+        assert_eq!(positions.ip, Position::Btn);
+        assert_eq!(positions.oop, Position::BB);
+
+        let positions = Positions::from_str("SB vs BB").unwrap();
+        assert_eq!(positions.ip, Position::BB);
+        assert_eq!(positions.oop, Position::SB);
+
+        let positions = Positions::from_str("LJ vs CO").unwrap();
+        assert_eq!(positions.ip, Position::CO);
+        assert_eq!(positions.oop, Position::LJ);
+    }
+
+    #[test]
+    fn test_positions_from_str_invalid() {
+        assert!(Positions::from_str("BTN BB").is_err());
+        assert!(Positions::from_str("BTN vs UTG").is_err());
+        assert!(Positions::from_str("BB vs BB").is_err());
+        assert!(Positions::from_str("BTN vs BB old").is_err());
+        assert!(Positions::from_str("btn vs bb").is_err());
+    }
+
+    #[test]
+    fn test_positions_display() {
+        assert_eq!(
+            Positions::from_str("BB vs BTN").unwrap().to_string(),
+            "BTN vs BB"
+        );
+        assert_eq!(
+            Positions::from_str("SB vs BB").unwrap().to_string(),
+            "SB vs BB"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     fmt::{self, Display, Formatter},
     ops::Sub,
+    str::FromStr,
 };
 
 use super::ParseError;
@@ -43,6 +44,24 @@ pub enum Rank {
     A,
 }
 
+impl Rank {
+    pub const ALL: [Rank; 13] = [
+        Rank::_2,
+        Rank::_3,
+        Rank::_4,
+        Rank::_5,
+        Rank::_6,
+        Rank::_7,
+        Rank::_8,
+        Rank::_9,
+        Rank::T,
+        Rank::J,
+        Rank::Q,
+        Rank::K,
+        Rank::A,
+    ];
+}
+
 impl TryFrom<char> for Rank {
     type Error = ParseError;
 
@@ -66,6 +85,18 @@ impl TryFrom<char> for Rank {
     }
 }
 
+impl FromStr for Rank {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Rank, ParseError> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Rank::try_from(c),
+            _ => Err(ParseError::str("rank", s)),
+        }
+    }
+}
+
 impl Display for Rank {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let c = match self {
@@ -114,8 +145,56 @@ impl Rank {
         }
     }
 
-    pub fn get_heights(&self) -> Vec<RankHeight> {
-        todo!()
+    // The bit of this rank in a rank mask, from bit 0 for deuces to bit 12 for aces
+    pub fn mask(&self) -> u16 {
+        1 << *self as u16
+    }
+}
+
+impl RankHeight {
+    // The ranks of this height as a rank mask
+    pub fn mask(&self) -> u16 {
+        Rank::ALL
+            .iter()
+            .filter(|rank| rank.is_height(self))
+            .fold(0, |mask, rank| mask | rank.mask())
+    }
+}
+
+// Ranks from low to high, both included
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct RankRange {
+    pub low: Rank,
+    pub high: Rank,
+}
+
+impl FromStr for RankRange {
+    type Err = ParseError;
+
+    // A single rank (K), a rank or higher (T+), a rank or lower (9-) or two ranks (T-Q)
+    fn from_str(s: &str) -> Result<RankRange, ParseError> {
+        let err = || ParseError::str("rank range", s);
+        let rank = |c: char| Rank::try_from(c).map_err(|_| err());
+
+        let chars: Vec<char> = s.chars().collect();
+        let (rank1, rank2) = match chars[..] {
+            [c] => (rank(c)?, rank(c)?),
+            [c, '+'] => (rank(c)?, Rank::A),
+            [c, '-'] => (Rank::_2, rank(c)?),
+            [c1, '-', c2] => (rank(c1)?, rank(c2)?),
+            _ => return Err(err()),
+        };
+
+        Ok(RankRange {
+            low: rank1.min(rank2),
+            high: rank1.max(rank2),
+        })
+    }
+}
+
+impl RankRange {
+    pub fn contains(&self, rank: Rank) -> bool {
+        (self.low..=self.high).contains(&rank)
     }
 }
 
@@ -244,6 +323,42 @@ mod tests {
         assert!(Rank::_5.is_height(&RankHeight::Wheel));
     }
 
+    #[test]
+    fn test_rank_height_mask() {
+        assert_eq!(RankHeight::Broadway.mask(), 0b1_1111_0000_0000);
+        assert_eq!(RankHeight::Middling.mask(), 0b0_0000_1110_0000);
+        assert_eq!(RankHeight::Low.mask(), 0b0_0000_0001_1111);
+        assert_eq!(RankHeight::Wheel.mask(), 0b1_0000_0000_1111);
+    }
+
+    #[test]
+    fn test_rank_from_str() {
+        assert_eq!(Rank::from_str("A").unwrap(), Rank::A);
+        assert_eq!(Rank::from_str("7").unwrap(), Rank::_7);
+        assert!(Rank::from_str("AK").is_err());
+        assert!(Rank::from_str("").is_err());
+    }
+
+    #[test]
+    fn test_rank_range_from_str() {
+        let range = |low, high| RankRange { low, high };
+
+        assert_eq!(RankRange::from_str("K").unwrap(), range(Rank::K, Rank::K));
+        assert_eq!(RankRange::from_str("T+").unwrap(), range(Rank::T, Rank::A));
+        assert_eq!(
+            RankRange::from_str("9-").unwrap(),
+            range(Rank::_2, Rank::_9)
+        );
+        assert_eq!(RankRange::from_str("Q-T").unwrap(), range(Rank::T, Rank::Q));
+        assert!(RankRange::from_str("T-").unwrap().contains(Rank::_4));
+        assert!(!RankRange::from_str("T-Q").unwrap().contains(Rank::K));
+
+        assert!(RankRange::from_str("").is_err());
+        assert!(RankRange::from_str("1+").is_err());
+        assert!(RankRange::from_str("T--").is_err());
+        assert!(RankRange::from_str("T-Q-K").is_err());
+    }
+
     #[test]
     fn test_rank_sub() {
         assert_eq!(Rank::A - Rank::K, 1);
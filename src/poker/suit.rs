@@ -2,7 +2,7 @@ use std::fmt::{self, Display, Formatter};
 
 use super::ParseError;
 
-#[derive(PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, Hash, Debug, PartialOrd, Ord, Clone, Copy)]
 pub enum Suit {
     Spade,
     Club,
@@ -10,6 +10,10 @@ pub enum Suit {
     Heart,
 }
 
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Spade, Suit::Club, Suit::Diamond, Suit::Heart];
+}
+
 const SPADE_CHAR: char = 's';
 const CLUB_CHAR: char = 'c';
 const DIAMOND_CHAR: char = 'd';